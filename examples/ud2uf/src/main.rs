@@ -29,9 +29,9 @@ fn convert(path: &Path, pretty: bool) -> Result<(), Box<std::io::Error>> {
 
     let br = BufReader::new(file);
 
-    let mut doc = treebank.create_doc();
+    let mut doc = treebank.create_doc().unwrap();
 
-    let mut sent = doc.create_sentence("en");
+    let mut sent = doc.create_sentence("en").unwrap();
 
     for line in br.lines() {
         let line = line.unwrap();
@@ -43,13 +43,13 @@ fn convert(path: &Path, pretty: bool) -> Result<(), Box<std::io::Error>> {
         if line.trim().is_empty() {
             doc.add_sentence(sent)
                 .expect("Failed to create sentence for line: {line}");
-            sent = doc.create_sentence("en");
+            sent = doc.create_sentence("en").unwrap();
             continue;
         }
 
         let annots = line.split("\t").collect::<Vec<&str>>();
 
-        let mut tok = sent.create_token(annots[FORM]);
+        let mut tok = sent.create_token(annots[FORM]).unwrap();
         if annots[LEMMA] != UNSPECIFIED {
             tok.set_lemma(annots[LEMMA]);
         }
@@ -0,0 +1,517 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use crate::{
+    schema::{Corpus, Sentence, Source, Token},
+    stats::ratio,
+    CorporeumError, TokenField,
+};
+
+/// Label of a token without a value.
+const NO_VALUE: &str = "_";
+
+/// Label of a span missing on one side, see [`Corpus::span_agreement()`].
+const NO_SPAN: &str = "O";
+
+/// Counts of label pairs of two annotations of the same items.
+///
+/// Rows are the labels of the first annotation (the corpus the method was
+/// called on), columns the labels of the second one. Printed with
+/// [`Display`](fmt::Display) as a tab-separated table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfusionMatrix {
+    counts: BTreeMap<(String, String), u64>,
+}
+
+impl ConfusionMatrix {
+    fn add(&mut self, ours: &str, theirs: &str) {
+        *self
+            .counts
+            .entry((ours.to_owned(), theirs.to_owned()))
+            .or_default() += 1;
+    }
+
+    /// Returns all labels of both annotations, sorted.
+    pub fn labels(&self) -> Vec<&str> {
+        self.counts
+            .keys()
+            .flat_map(|(a, b)| [a.as_str(), b.as_str()])
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Returns how many items got label `ours` in the first and `theirs` in the second annotation.
+    pub fn count(&self, ours: &str, theirs: &str) -> u64 {
+        self.counts
+            .get(&(ours.to_owned(), theirs.to_owned()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of items.
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+}
+
+impl fmt::Display for ConfusionMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let labels = self.labels();
+        for label in &labels {
+            write!(f, "\t{label}")?;
+        }
+        writeln!(f)?;
+
+        for ours in &labels {
+            f.write_str(ours)?;
+            for theirs in &labels {
+                write!(f, "\t{}", self.count(ours, theirs))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Agreement of two annotations of a token field, see [`Corpus::agreement()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Agreement {
+    observed: f64,
+    expected: f64,
+    confusion: ConfusionMatrix,
+}
+
+impl Agreement {
+    /// Returns the number of compared tokens.
+    pub fn items(&self) -> u64 {
+        self.confusion.total()
+    }
+
+    /// Returns the share of tokens with the same label.
+    pub const fn observed(&self) -> f64 {
+        self.observed
+    }
+
+    /// Returns the agreement expected by chance from the label distributions.
+    pub const fn expected(&self) -> f64 {
+        self.expected
+    }
+
+    /// Returns Cohen's kappa, `(observed - expected) / (1 - expected)`.
+    pub fn kappa(&self) -> f64 {
+        kappa(self.observed, self.expected)
+    }
+
+    pub const fn confusion(&self) -> &ConfusionMatrix {
+        &self.confusion
+    }
+}
+
+/// Agreement of two dependency annotations, see [`Corpus::attachment_agreement()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttachmentAgreement {
+    tokens: u64,
+    same_head: u64,
+    same_deprel: u64,
+    same_both: u64,
+    deprels: ConfusionMatrix,
+}
+
+impl AttachmentAgreement {
+    /// Returns the number of compared tokens.
+    pub const fn tokens(&self) -> u64 {
+        self.tokens
+    }
+
+    /// Returns the share of tokens with the same head (unlabeled attachment).
+    pub fn uas(&self) -> f64 {
+        ratio(self.same_head, self.tokens)
+    }
+
+    /// Returns the share of tokens with the same head and dependency relation
+    /// (labeled attachment).
+    pub fn las(&self) -> f64 {
+        ratio(self.same_both, self.tokens)
+    }
+
+    /// Returns the share of tokens with the same dependency relation.
+    pub fn label_accuracy(&self) -> f64 {
+        ratio(self.same_deprel, self.tokens)
+    }
+
+    /// Returns the confusion matrix of the dependency relations.
+    pub const fn deprels(&self) -> &ConfusionMatrix {
+        &self.deprels
+    }
+}
+
+/// Precision, recall and F1 score of the spans of one annotation measured
+/// against another.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpanScore {
+    matched: u64,
+    ours: u64,
+    theirs: u64,
+}
+
+impl SpanScore {
+    /// Returns the number of spans with the same boundaries and label in both annotations.
+    pub const fn matched(&self) -> u64 {
+        self.matched
+    }
+
+    /// Returns the share of the spans of the second annotation found in the first.
+    pub fn precision(&self) -> f64 {
+        ratio(self.matched, self.theirs)
+    }
+
+    /// Returns the share of the spans of the first annotation found in the second.
+    pub fn recall(&self) -> f64 {
+        ratio(self.matched, self.ours)
+    }
+
+    pub fn f1(&self) -> f64 {
+        ratio(2 * self.matched, self.ours + self.theirs)
+    }
+}
+
+/// Agreement of two span annotations, see [`Corpus::span_agreement()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanAgreement {
+    total: SpanScore,
+    labels: BTreeMap<String, SpanScore>,
+    confusion: ConfusionMatrix,
+}
+
+impl SpanAgreement {
+    /// Returns the scores over all spans.
+    pub const fn total(&self) -> SpanScore {
+        self.total
+    }
+
+    /// Returns the scores of the spans with a label.
+    pub fn label(&self, label: &str) -> SpanScore {
+        self.labels.get(label).copied().unwrap_or_default()
+    }
+
+    /// Returns the span labels of both annotations.
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.labels.keys().map(String::as_str)
+    }
+
+    /// Returns the confusion matrix of the labels of spans with the same
+    /// boundaries, `O` standing for a span missing on one side.
+    pub const fn confusion(&self) -> &ConfusionMatrix {
+        &self.confusion
+    }
+}
+
+fn kappa(observed: f64, expected: f64) -> f64 {
+    if expected >= 1.0 {
+        1.0
+    } else {
+        (observed - expected) / (1.0 - expected)
+    }
+}
+
+/// Pairs the source sentences of two corpora with the same document and sentence IDs.
+fn aligned_sentences<'a>(
+    ours: &'a Corpus,
+    theirs: &'a Corpus,
+) -> Vec<(&'a Sentence<Source>, &'a Sentence<Source>)> {
+    ours.documents
+        .iter()
+        .filter_map(|doc| Some((doc, theirs.doc(doc.id)?)))
+        .flat_map(|(doc, other)| {
+            doc.sentences
+                .iter()
+                .filter_map(|sent| Some((sent, other.sentence(sent.id)?)))
+        })
+        .collect()
+}
+
+/// Pairs the tokens of two corpora with the same document, sentence and token IDs.
+fn aligned_tokens<'a>(ours: &'a Corpus, theirs: &'a Corpus) -> Vec<(&'a Token, &'a Token)> {
+    aligned_sentences(ours, theirs)
+        .into_iter()
+        .flat_map(|(sent, other)| {
+            sent.tokens
+                .iter()
+                .filter_map(|tok| Some((tok, other.token(tok.id)?)))
+        })
+        .collect()
+}
+
+/// Reads labeled spans from BIO tags (`B-X`, `I-X`, `O`) and BIOES tags,
+/// where `E-X` ends a span and `S-X` is a span of a single token. An `I-` or
+/// `E-` tag without an open span of the same label starts a new span.
+fn spans<'a, F: Fn(&'a Token) -> Option<&'a str>>(
+    sent: &'a Sentence<Source>,
+    tags: &F,
+) -> Vec<(u32, u32, &'a str)> {
+    let mut spans: Vec<(u32, u32, &str)> = Vec::new();
+    let mut open = false;
+
+    for tok in &sent.tokens {
+        let tag = tags(tok).unwrap_or(NO_SPAN);
+        let (prefix, label) = tag.split_once('-').unwrap_or(("O", ""));
+        match prefix {
+            "I" | "E" if open && spans.last().is_some_and(|s| s.2 == label) => {
+                if let Some(span) = spans.last_mut() {
+                    span.1 = tok.id;
+                }
+                open = prefix == "I";
+            }
+            "B" | "I" | "S" | "E" => {
+                spans.push((tok.id, tok.id, label));
+                open = matches!(prefix, "B" | "I");
+            }
+            _ => open = false,
+        }
+    }
+
+    spans
+}
+
+impl Corpus {
+    /// Compares the values of a token field in this corpus with a second
+    /// annotation of the same text, e.g. UPOS tags of two annotators.
+    ///
+    /// Tokens are aligned by document, sentence and token ID; tokens found in
+    /// only one corpus are ignored and a missing value counts as the label `_`.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, TokenField, Tokenizer};
+    ///
+    /// let mut ann1 = Corpus::new();
+    /// ann1.import_text("Time flies like an arrow", &Tokenizer::new("en")).unwrap();
+    /// let mut ann2 = ann1.clone();
+    ///
+    /// let tag = |corp: &mut Corpus, tags: [&str; 5]| {
+    ///     let sent = corp.doc_mut(0).unwrap().sentence_mut(0).unwrap();
+    ///     for (id, tag) in (0..).zip(tags) {
+    ///         sent.token_mut(id).unwrap().set(TokenField::Upos, tag);
+    ///     }
+    /// };
+    /// tag(&mut ann1, ["NOUN", "VERB", "ADP", "DET", "NOUN"]);
+    /// tag(&mut ann2, ["NOUN", "NOUN", "VERB", "DET", "NOUN"]);
+    ///
+    /// let agreement = ann1.agreement(&ann2, TokenField::Upos);
+    /// assert_eq!(agreement.observed(), 0.6);
+    /// assert!((agreement.kappa() - 0.412).abs() < 0.001);
+    /// assert_eq!(agreement.confusion().count("VERB", "NOUN"), 1);
+    ///
+    /// let fleiss = ann1.fleiss_kappa(&[&ann2], TokenField::Upos).unwrap();
+    /// assert!((fleiss - 0.394).abs() < 0.001);
+    /// ```
+    pub fn agreement(&self, other: &Self, field: TokenField) -> Agreement {
+        let mut confusion = ConfusionMatrix::default();
+        for (ours, theirs) in aligned_tokens(self, other) {
+            confusion.add(
+                ours.get(field).unwrap_or(NO_VALUE),
+                theirs.get(field).unwrap_or(NO_VALUE),
+            );
+        }
+
+        let total = confusion.total();
+        let mut same = 0;
+        let mut ours: HashMap<&str, u64> = HashMap::new();
+        let mut theirs: HashMap<&str, u64> = HashMap::new();
+        for ((a, b), &count) in &confusion.counts {
+            if a == b {
+                same += count;
+            }
+            *ours.entry(a).or_default() += count;
+            *theirs.entry(b).or_default() += count;
+        }
+        let expected = ours
+            .iter()
+            .map(|(label, &n)| {
+                ratio(n, total) * ratio(theirs.get(label).copied().unwrap_or(0), total)
+            })
+            .sum();
+
+        Agreement {
+            observed: ratio(same, total),
+            expected,
+            confusion,
+        }
+    }
+
+    /// Computes Fleiss' kappa of a token field over this corpus and further
+    /// annotations of the same text.
+    ///
+    /// Only tokens present in all corpora (by document, sentence and token ID)
+    /// are rated; a missing value counts as the label `_`. With a single other
+    /// annotation the result is close to, but not the same as, Cohen's kappa
+    /// from [`agreement()`](Self::agreement).
+    ///
+    /// # Errors
+    /// This will return an error if `others` is empty, as agreement needs at
+    /// least two raters.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn fleiss_kappa(&self, others: &[&Self], field: TokenField) -> Result<f64, CorporeumError> {
+        if others.is_empty() {
+            return Err(CorporeumError::BadValue(
+                "Fleiss' kappa needs at least two annotations".to_owned(),
+            ));
+        }
+
+        let raters = others.len() + 1;
+        let mut items = 0;
+        let mut agreement = 0.0;
+        let mut categories: HashMap<&str, u64> = HashMap::new();
+
+        for (doc, sent) in self
+            .documents
+            .iter()
+            .flat_map(|doc| doc.sentences.iter().map(move |sent| (doc.id, sent)))
+        {
+            let others: Option<Vec<&Sentence<Source>>> = others
+                .iter()
+                .map(|corp| corp.doc(doc)?.sentence(sent.id))
+                .collect();
+            let Some(others) = others else {
+                continue;
+            };
+
+            for tok in &sent.tokens {
+                let Some(ratings) = others
+                    .iter()
+                    .map(|other| other.token(tok.id))
+                    .collect::<Option<Vec<&Token>>>()
+                else {
+                    continue;
+                };
+
+                let mut counts: HashMap<&str, u64> = HashMap::new();
+                for rating in ratings.into_iter().chain([tok]) {
+                    *counts
+                        .entry(rating.get(field).unwrap_or(NO_VALUE))
+                        .or_default() += 1;
+                }
+
+                let pairs: u64 = counts.values().map(|n| n * (n - 1)).sum();
+                agreement += ratio(pairs, (raters * (raters - 1)) as u64);
+                for (label, n) in counts {
+                    *categories.entry(label).or_default() += n;
+                }
+                items += 1;
+            }
+        }
+
+        let ratings = items * raters as u64;
+        let observed = if items == 0 {
+            0.0
+        } else {
+            agreement / items as f64
+        };
+        let expected = categories
+            .values()
+            .map(|&n| ratio(n, ratings).powi(2))
+            .sum();
+        Ok(kappa(observed, expected))
+    }
+
+    /// Compares heads and dependency relations of this corpus with a second
+    /// annotation of the same text, aligned by IDs as in [`agreement()`](Self::agreement).
+    ///
+    /// Heads are compared as written, both annotations are expected to number
+    /// tokens the same way.
+    pub fn attachment_agreement(&self, other: &Self) -> AttachmentAgreement {
+        let mut result = AttachmentAgreement {
+            tokens: 0,
+            same_head: 0,
+            same_deprel: 0,
+            same_both: 0,
+            deprels: ConfusionMatrix::default(),
+        };
+
+        for (ours, theirs) in aligned_tokens(self, other) {
+            let same_head = ours.head == theirs.head;
+            let same_deprel = ours.deprel == theirs.deprel;
+
+            result.tokens += 1;
+            result.same_head += u64::from(same_head);
+            result.same_deprel += u64::from(same_deprel);
+            result.same_both += u64::from(same_head && same_deprel);
+            result.deprels.add(
+                ours.deprel().unwrap_or(NO_VALUE),
+                theirs.deprel().unwrap_or(NO_VALUE),
+            );
+        }
+
+        result
+    }
+
+    /// Compares labeled spans, e.g. named entities, of this corpus with a
+    /// second annotation of the same text.
+    ///
+    /// `tags` returns the BIO tag of a token (`B-PER`, `I-PER`, `O`), e.g. from
+    /// a `misc` entry. BIOES tags (`S-PER`, `E-PER`) are read as well. Spans are compared within sentences aligned by IDs and
+    /// match if they have the same first and last token and the same label.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, Tokenizer};
+    ///
+    /// let mut ann1 = Corpus::new();
+    /// ann1.import_text("Ada Lovelace met Charles Babbage", &Tokenizer::new("en")).unwrap();
+    /// let mut ann2 = ann1.clone();
+    ///
+    /// let tag = |corp: &mut Corpus, tags: [&str; 5]| {
+    ///     let sent = corp.doc_mut(0).unwrap().sentence_mut(0).unwrap();
+    ///     for (id, tag) in (0..).zip(tags) {
+    ///         sent.token_mut(id).unwrap().set_misc_value("NE", tag);
+    ///     }
+    /// };
+    /// tag(&mut ann1, ["B-PER", "I-PER", "O", "B-PER", "I-PER"]);
+    /// tag(&mut ann2, ["B-PER", "I-PER", "O", "B-ORG", "I-ORG"]);
+    ///
+    /// let spans = ann1.span_agreement(&ann2, |tok| tok.misc_value("NE"));
+    /// assert_eq!(spans.total().f1(), 0.5);
+    /// assert_eq!(spans.label("PER").recall(), 0.5);
+    /// assert_eq!(spans.confusion().count("PER", "ORG"), 1);
+    /// ```
+    pub fn span_agreement<F>(&self, other: &Self, tags: F) -> SpanAgreement
+    where
+        F: Fn(&Token) -> Option<&str>,
+    {
+        let mut total = SpanScore::default();
+        let mut labels: BTreeMap<String, SpanScore> = BTreeMap::new();
+        let mut confusion = ConfusionMatrix::default();
+
+        for (sent, other) in aligned_sentences(self, other) {
+            let ours = spans(sent, &tags);
+            let theirs = spans(other, &tags);
+
+            for &(first, last, label) in &ours {
+                total.ours += 1;
+                labels.entry(label.to_owned()).or_default().ours += 1;
+                if theirs.contains(&(first, last, label)) {
+                    total.matched += 1;
+                    labels.entry(label.to_owned()).or_default().matched += 1;
+                }
+
+                let same = theirs.iter().find(|s| (s.0, s.1) == (first, last));
+                confusion.add(label, same.map_or(NO_SPAN, |s| s.2));
+            }
+
+            for &(first, last, label) in &theirs {
+                total.theirs += 1;
+                labels.entry(label.to_owned()).or_default().theirs += 1;
+                if !ours.iter().any(|s| (s.0, s.1) == (first, last)) {
+                    confusion.add(NO_SPAN, label);
+                }
+            }
+        }
+
+        SpanAgreement {
+            total,
+            labels,
+            confusion,
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use crate::{query::Query, schema::Corpus, tree::Context, TokenField};
+
+/// Where collocates of a node are looked for, see [`Corpus::collocations()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollocationWindow {
+    /// Tokens at most `left` positions before and `right` positions after the
+    /// node, within its sentence.
+    Span { left: usize, right: usize },
+    /// The head and the dependents of the node tokens.
+    Dependency,
+}
+
+/// Association measure used to rank collocates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssociationMeasure {
+    /// Pointwise mutual information, `log2(f_nc * N / (f_n * f_c))`.
+    MutualInformation,
+    /// T-score, `(f_nc - f_n * f_c / N) / sqrt(f_nc)`.
+    TScore,
+    /// Log-likelihood (G²) of the 2×2 contingency table.
+    LogLikelihood,
+    /// logDice, `14 + log2(2 * f_nc / (f_n + f_c))`.
+    LogDice,
+}
+
+/// A collocate of a node with its association scores.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Collocate {
+    value: String,
+    cooccurrences: u64,
+    freq: u64,
+    mi: f64,
+    t_score: f64,
+    log_likelihood: f64,
+    log_dice: f64,
+}
+
+impl Collocate {
+    /// Returns the value of the collocate field, e.g. its lemma.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Returns how many times the collocate occurs in the window of a node.
+    pub const fn cooccurrences(&self) -> u64 {
+        self.cooccurrences
+    }
+
+    /// Returns the frequency of the collocate in the whole corpus.
+    pub const fn freq(&self) -> u64 {
+        self.freq
+    }
+
+    /// Returns the score of the given measure.
+    pub const fn score(&self, measure: AssociationMeasure) -> f64 {
+        match measure {
+            AssociationMeasure::MutualInformation => self.mi,
+            AssociationMeasure::TScore => self.t_score,
+            AssociationMeasure::LogLikelihood => self.log_likelihood,
+            AssociationMeasure::LogDice => self.log_dice,
+        }
+    }
+}
+
+/// `O * ln(O / E)`, with the convention `0 * ln(0) = 0`.
+fn ll_term(observed: f64, expected: f64) -> f64 {
+    if observed > 0.0 && expected > 0.0 {
+        observed * (observed / expected).ln()
+    } else {
+        0.0
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn scores(value: String, f_nc: u64, f_n: u64, f_c: u64, total: u64) -> Collocate {
+    let (o11, f_n_f, f_c_f, n) = (f_nc as f64, f_n as f64, f_c as f64, total as f64);
+    let expected = f_n_f * f_c_f / n;
+
+    // a node may co-occur with a collocate more than once, keep the table non-negative
+    let o12 = (f_n_f - o11).max(0.0);
+    let o21 = (f_c_f - o11).max(0.0);
+    let o22 = (n - o11 - o12 - o21).max(0.0);
+    let (r1, r2, c1, c2) = (o11 + o12, o21 + o22, o11 + o21, o12 + o22);
+    let log_likelihood = 2.0
+        * (ll_term(o11, r1 * c1 / n)
+            + ll_term(o12, r1 * c2 / n)
+            + ll_term(o21, r2 * c1 / n)
+            + ll_term(o22, r2 * c2 / n));
+
+    Collocate {
+        value,
+        cooccurrences: f_nc,
+        freq: f_c,
+        mi: (o11 / expected).log2(),
+        t_score: (o11 - expected) / o11.sqrt(),
+        log_likelihood,
+        log_dice: 14.0 + (2.0 * o11 / (f_n_f + f_c_f)).log2(),
+    }
+}
+
+impl Corpus {
+    /// Finds the collocates of a node and ranks them by an association measure,
+    /// highest score first.
+    ///
+    /// The node is every match of `node`; collocates are compared by the value
+    /// of `field`. `f_n` is the number of node matches, `f_c` the corpus frequency
+    /// of the collocate value, `f_nc` the number of its occurrences in the node
+    /// windows and `N` the number of tokens in the corpus. Tokens of the match
+    /// itself are never collocates.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{AssociationMeasure, CollocationWindow, Corpus, Query, TokenField, Tokenizer};
+    ///
+    /// let mut corp = Corpus::new();
+    /// let tokenizer = Tokenizer::new("en");
+    /// corp.import_text(
+    ///     "I like strong tea. I drink strong tea. The wind is strong. Tea is hot.",
+    ///     &tokenizer,
+    /// )
+    /// .unwrap();
+    ///
+    /// let node = Query::parse(r#""tea"%c"#).unwrap();
+    /// let window = CollocationWindow::Span { left: 1, right: 0 };
+    /// let collocates = corp.collocations(&node, TokenField::Form, window, AssociationMeasure::LogDice);
+    ///
+    /// assert_eq!(collocates[0].value(), "strong");
+    /// assert_eq!(collocates[0].cooccurrences(), 2);
+    /// ```
+    pub fn collocations(
+        &self,
+        node: &Query,
+        field: TokenField,
+        window: CollocationWindow,
+        measure: AssociationMeasure,
+    ) -> Vec<Collocate> {
+        let mut cooccurrences: HashMap<&str, u64> = HashMap::new();
+        let mut nodes = 0;
+
+        for doc in &self.documents {
+            for sent in &doc.sentences {
+                let ctx = Context::new(sent);
+                for range in node.find_in(sent) {
+                    nodes += 1;
+
+                    let collocates: Vec<usize> = match window {
+                        CollocationWindow::Span { left, right } => {
+                            (range.start.saturating_sub(left)..range.start)
+                                .chain(
+                                    range.end
+                                        ..range.end.saturating_add(right).min(sent.tokens.len()),
+                                )
+                                .collect()
+                        }
+                        CollocationWindow::Dependency => {
+                            let mut related: Vec<usize> = range
+                                .clone()
+                                .filter_map(|i| ctx.parents[i])
+                                .chain(ctx.parents.iter().enumerate().filter_map(|(i, p)| {
+                                    p.filter(|p| range.contains(p)).map(|_| i)
+                                }))
+                                .filter(|i| !range.contains(i))
+                                .collect();
+                            related.sort_unstable();
+                            related.dedup();
+                            related
+                        }
+                    };
+
+                    for i in collocates {
+                        let value = sent.tokens[i].get(field).unwrap_or_default();
+                        *cooccurrences.entry(value).or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        let freqs = self.frequency_list(&[field]);
+        let mut collocates: Vec<Collocate> = cooccurrences
+            .into_iter()
+            .map(|(value, f_nc)| {
+                scores(
+                    value.to_owned(),
+                    f_nc,
+                    nodes,
+                    freqs.freq(&[value]),
+                    freqs.total_tokens(),
+                )
+            })
+            .collect();
+
+        collocates.sort_by(|a, b| {
+            b.score(measure)
+                .total_cmp(&a.score(measure))
+                .then_with(|| a.value.cmp(&b.value))
+        });
+        collocates
+    }
+}
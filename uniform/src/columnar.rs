@@ -0,0 +1,313 @@
+use std::io::Write;
+use std::sync::Arc;
+
+use arrow_array::builder::{StringBuilder, UInt32Builder, UInt64Builder};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+
+use crate::{
+    schema::{Corpus, Sentence, Token},
+    CorporeumError,
+};
+
+/// Builds the columns of the token table row by row.
+struct TokenColumns {
+    doc_id: UInt32Builder,
+    sentence_id: UInt32Builder,
+    translation_id: UInt32Builder,
+    token_id: UInt32Builder,
+    form: StringBuilder,
+    lemma: StringBuilder,
+    upos: StringBuilder,
+    xpos: StringBuilder,
+    feats: StringBuilder,
+    head: StringBuilder,
+    deprel: StringBuilder,
+    deps: StringBuilder,
+    misc: StringBuilder,
+    gloss: StringBuilder,
+    start: UInt64Builder,
+    end: UInt64Builder,
+}
+
+impl TokenColumns {
+    fn new() -> Self {
+        Self {
+            doc_id: UInt32Builder::new(),
+            sentence_id: UInt32Builder::new(),
+            translation_id: UInt32Builder::new(),
+            token_id: UInt32Builder::new(),
+            form: StringBuilder::new(),
+            lemma: StringBuilder::new(),
+            upos: StringBuilder::new(),
+            xpos: StringBuilder::new(),
+            feats: StringBuilder::new(),
+            head: StringBuilder::new(),
+            deprel: StringBuilder::new(),
+            deps: StringBuilder::new(),
+            misc: StringBuilder::new(),
+            gloss: StringBuilder::new(),
+            start: UInt64Builder::new(),
+            end: UInt64Builder::new(),
+        }
+    }
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("doc_id", DataType::UInt32, false),
+            Field::new("sentence_id", DataType::UInt32, false),
+            Field::new("translation_id", DataType::UInt32, true),
+            Field::new("token_id", DataType::UInt32, false),
+            Field::new("form", DataType::Utf8, false),
+            Field::new("lemma", DataType::Utf8, true),
+            Field::new("upos", DataType::Utf8, true),
+            Field::new("xpos", DataType::Utf8, true),
+            Field::new("feats", DataType::Utf8, true),
+            Field::new("head", DataType::Utf8, true),
+            Field::new("deprel", DataType::Utf8, true),
+            Field::new("deps", DataType::Utf8, true),
+            Field::new("misc", DataType::Utf8, true),
+            Field::new("gloss", DataType::Utf8, true),
+            Field::new("start", DataType::UInt64, true),
+            Field::new("end", DataType::UInt64, true),
+        ])
+    }
+
+    fn push(&mut self, doc_id: u32, sentence_id: u32, translation_id: Option<u32>, tok: &Token) {
+        self.doc_id.append_value(doc_id);
+        self.sentence_id.append_value(sentence_id);
+        self.translation_id.append_option(translation_id);
+        self.token_id.append_value(tok.id);
+        self.form.append_value(&tok.form);
+        self.lemma.append_option(tok.lemma.as_deref());
+        self.upos.append_option(tok.upos.as_deref());
+        self.xpos.append_option(tok.xpos.as_deref());
+        self.feats.append_option(tok.feats.as_deref());
+        self.head.append_option(tok.head.as_deref());
+        self.deprel.append_option(tok.deprel.as_deref());
+        self.deps.append_option(tok.deps.as_deref());
+        self.misc.append_option(tok.misc.as_deref());
+        self.gloss.append_option(tok.gloss.as_deref());
+        self.start.append_option(tok.start);
+        self.end.append_option(tok.end);
+    }
+
+    fn finish(mut self) -> Result<RecordBatch, CorporeumError> {
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(self.doc_id.finish()),
+            Arc::new(self.sentence_id.finish()),
+            Arc::new(self.translation_id.finish()),
+            Arc::new(self.token_id.finish()),
+            Arc::new(self.form.finish()),
+            Arc::new(self.lemma.finish()),
+            Arc::new(self.upos.finish()),
+            Arc::new(self.xpos.finish()),
+            Arc::new(self.feats.finish()),
+            Arc::new(self.head.finish()),
+            Arc::new(self.deprel.finish()),
+            Arc::new(self.deps.finish()),
+            Arc::new(self.misc.finish()),
+            Arc::new(self.gloss.finish()),
+            Arc::new(self.start.finish()),
+            Arc::new(self.end.finish()),
+        ];
+
+        Ok(RecordBatch::try_new(Arc::new(Self::schema()), columns)?)
+    }
+}
+
+/// Builds the columns of the sentence table row by row.
+struct SentenceColumns {
+    doc_id: UInt32Builder,
+    sentence_id: UInt32Builder,
+    translation_id: UInt32Builder,
+    lang: StringBuilder,
+    text: StringBuilder,
+    token_count: UInt32Builder,
+    speaker: StringBuilder,
+    start: UInt64Builder,
+    end: UInt64Builder,
+}
+
+impl SentenceColumns {
+    fn new() -> Self {
+        Self {
+            doc_id: UInt32Builder::new(),
+            sentence_id: UInt32Builder::new(),
+            translation_id: UInt32Builder::new(),
+            lang: StringBuilder::new(),
+            text: StringBuilder::new(),
+            token_count: UInt32Builder::new(),
+            speaker: StringBuilder::new(),
+            start: UInt64Builder::new(),
+            end: UInt64Builder::new(),
+        }
+    }
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("doc_id", DataType::UInt32, false),
+            Field::new("sentence_id", DataType::UInt32, false),
+            Field::new("translation_id", DataType::UInt32, true),
+            Field::new("lang", DataType::Utf8, false),
+            Field::new("text", DataType::Utf8, false),
+            Field::new("token_count", DataType::UInt32, false),
+            Field::new("speaker", DataType::Utf8, true),
+            Field::new("start", DataType::UInt64, true),
+            Field::new("end", DataType::UInt64, true),
+        ])
+    }
+
+    fn push<T>(
+        &mut self,
+        doc_id: u32,
+        sentence_id: u32,
+        translation_id: Option<u32>,
+        sent: &Sentence<T>,
+    ) {
+        self.doc_id.append_value(doc_id);
+        self.sentence_id.append_value(sentence_id);
+        self.translation_id.append_option(translation_id);
+        self.lang.append_value(&sent.lang);
+        self.text.append_value(sent.to_text());
+        self.token_count
+            .append_value(u32::try_from(sent.tokens.len()).unwrap_or(u32::MAX));
+        self.speaker.append_option(sent.speaker.as_deref());
+        self.start.append_option(sent.start);
+        self.end.append_option(sent.end);
+    }
+
+    fn finish(mut self) -> Result<RecordBatch, CorporeumError> {
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(self.doc_id.finish()),
+            Arc::new(self.sentence_id.finish()),
+            Arc::new(self.translation_id.finish()),
+            Arc::new(self.lang.finish()),
+            Arc::new(self.text.finish()),
+            Arc::new(self.token_count.finish()),
+            Arc::new(self.speaker.finish()),
+            Arc::new(self.start.finish()),
+            Arc::new(self.end.finish()),
+        ];
+
+        Ok(RecordBatch::try_new(Arc::new(Self::schema()), columns)?)
+    }
+}
+
+fn write_parquet<W: Write + Send>(batch: &RecordBatch, dest: W) -> Result<(), CorporeumError> {
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+
+    let mut writer = ArrowWriter::try_new(dest, batch.schema(), Some(props))?;
+    writer.write(batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+impl Corpus {
+    /// Flattens all tokens of the corpus into an Arrow table.
+    ///
+    /// Every row holds `doc_id`, `sentence_id`, `translation_id` and `token_id`
+    /// followed by all [`Token`](crate::Token) fields and the token `start` and
+    /// `end` offsets. Tokens of source sentences
+    /// have a null `translation_id`, tokens of translations carry the ID of the
+    /// translation they belong to.
+    ///
+    /// # Example
+    /// ```
+    /// # use uniform::Corpus;
+    /// let mut corp = Corpus::new();
+    /// let mut doc = corp.create_doc().unwrap();
+    /// let mut sent = doc.create_sentence("en").unwrap();
+    /// for form in ["Hello", "world"] {
+    ///     let tok = sent.create_token(form).unwrap();
+    ///     sent.add_token(tok);
+    /// }
+    /// doc.add_sentence(sent).unwrap();
+    /// corp.add_doc(doc).unwrap();
+    ///
+    /// let batch = corp.token_batch().unwrap();
+    /// assert_eq!(batch.num_rows(), 2);
+    /// assert_eq!(batch.schema().field(4).name(), "form");
+    /// ```
+    ///
+    /// # Errors
+    /// This will return an error if the columns could not be assembled into a batch.
+    pub fn token_batch(&self) -> Result<RecordBatch, CorporeumError> {
+        let mut columns = TokenColumns::new();
+
+        for doc in &self.documents {
+            for sent in &doc.sentences {
+                for tok in &sent.tokens {
+                    columns.push(doc.id, sent.id, None, tok);
+                }
+
+                for trans in &sent.translations {
+                    for tok in &trans.tokens {
+                        columns.push(doc.id, sent.id, Some(trans.id), tok);
+                    }
+                }
+            }
+        }
+
+        columns.finish()
+    }
+
+    /// Flattens all sentences of the corpus into an Arrow table.
+    ///
+    /// Every row holds `doc_id`, `sentence_id`, `translation_id`, `lang`, `text`,
+    /// `token_count`, `speaker`, `start` and `end`. Translations are stored as separate rows with their
+    /// `translation_id` set.
+    ///
+    /// # Errors
+    /// This will return an error if the columns could not be assembled into a batch.
+    pub fn sentence_batch(&self) -> Result<RecordBatch, CorporeumError> {
+        let mut columns = SentenceColumns::new();
+
+        for doc in &self.documents {
+            for sent in &doc.sentences {
+                columns.push(doc.id, sent.id, None, sent);
+
+                for trans in &sent.translations {
+                    columns.push(doc.id, sent.id, Some(trans.id), trans);
+                }
+            }
+        }
+
+        columns.finish()
+    }
+
+    /// Save the token table (see [`token_batch()`](Self::token_batch)) as a Parquet file.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use uniform::Corpus;
+    /// # use std::fs::File;
+    /// let corp = Corpus::load(File::open("some_file.ucf").unwrap()).unwrap();
+    ///
+    /// corp.save_tokens_parquet(File::create("tokens.parquet").unwrap()).unwrap();
+    /// corp.save_sentences_parquet(File::create("sentences.parquet").unwrap()).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// This will return an error if:
+    /// - The table could not be built
+    /// - Encoding or writing the Parquet file fails
+    pub fn save_tokens_parquet<W: Write + Send>(&self, dest: W) -> Result<(), CorporeumError> {
+        write_parquet(&self.token_batch()?, dest)
+    }
+
+    /// Save the sentence table (see [`sentence_batch()`](Self::sentence_batch)) as a Parquet file.
+    ///
+    /// # Errors
+    /// This will return an error if:
+    /// - The table could not be built
+    /// - Encoding or writing the Parquet file fails
+    pub fn save_sentences_parquet<W: Write + Send>(&self, dest: W) -> Result<(), CorporeumError> {
+        write_parquet(&self.sentence_batch()?, dest)
+    }
+}
@@ -0,0 +1,260 @@
+use std::cmp::Ordering;
+use std::io::Write;
+use std::ops::Range;
+
+use quick_xml::escape::escape;
+
+use crate::{
+    query::Match,
+    schema::{Corpus, Token},
+    sentence::join_tokens,
+    CorporeumError, TokenField,
+};
+
+/// Amount of context shown around the keyword of a concordance line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextWidth {
+    /// At most this many tokens on each side.
+    Tokens(usize),
+    /// At most this many characters on each side, cut in the middle of a token if needed.
+    Chars(usize),
+}
+
+/// Order of concordance lines, see [`Concordance::sort()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// By the left context, starting with the token nearest to the keyword.
+    Left,
+    /// By the keyword.
+    Keyword,
+    /// By the right context, starting with the token following the keyword.
+    Right,
+    /// By the given attribute of the keyword tokens.
+    Attribute(TokenField),
+}
+
+/// One keyword-in-context line.
+#[derive(Debug, Clone)]
+pub struct KwicLine<'a> {
+    doc_id: u32,
+    sentence_id: u32,
+    range: Range<usize>,
+    left: &'a [Token],
+    keyword: &'a [Token],
+    right: &'a [Token],
+    width: ContextWidth,
+}
+
+impl<'a> KwicLine<'a> {
+    pub const fn doc_id(&self) -> u32 {
+        self.doc_id
+    }
+
+    pub const fn sentence_id(&self) -> u32 {
+        self.sentence_id
+    }
+
+    /// Returns the range of keyword tokens as indices into the tokens of the sentence.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns the tokens of the left context. With [`ContextWidth::Chars`]
+    /// these are all preceding tokens of the sentence.
+    pub const fn left_tokens(&self) -> &'a [Token] {
+        self.left
+    }
+
+    pub const fn keyword_tokens(&self) -> &'a [Token] {
+        self.keyword
+    }
+
+    /// Returns the tokens of the right context. With [`ContextWidth::Chars`]
+    /// these are all following tokens of the sentence.
+    pub const fn right_tokens(&self) -> &'a [Token] {
+        self.right
+    }
+
+    /// Returns the left context as text.
+    pub fn left(&self) -> String {
+        let text = join_tokens(self.left);
+        match self.width {
+            ContextWidth::Tokens(_) => text,
+            ContextWidth::Chars(n) => {
+                let skip = text.chars().count().saturating_sub(n);
+                text.chars().skip(skip).collect()
+            }
+        }
+    }
+
+    pub fn keyword(&self) -> String {
+        join_tokens(self.keyword)
+    }
+
+    /// Returns the right context as text.
+    pub fn right(&self) -> String {
+        let text = join_tokens(self.right);
+        match self.width {
+            ContextWidth::Tokens(_) => text,
+            ContextWidth::Chars(n) => text.chars().take(n).collect(),
+        }
+    }
+}
+
+/// A list of keyword-in-context lines built from query matches.
+///
+/// # Example
+/// ```
+/// use uniform::{ContextWidth, Corpus, Query, SortKey, Tokenizer};
+///
+/// let mut corp = Corpus::new();
+/// let tokenizer = Tokenizer::new("en");
+/// corp.import_text("The dog barked. A cat saw the dog. Every dog sleeps.", &tokenizer)
+///     .unwrap();
+///
+/// let matches = corp.query(&Query::parse(r#""dog""#).unwrap());
+/// let mut conc = corp.concordance(&matches, ContextWidth::Tokens(2));
+/// conc.sort(SortKey::Right);
+///
+/// let lines: Vec<String> = conc
+///     .lines()
+///     .iter()
+///     .map(|l| format!("{} [{}] {}", l.left(), l.keyword(), l.right()))
+///     .collect();
+/// assert_eq!(lines, ["saw the [dog] .", "The [dog] barked.", "Every [dog] sleeps."]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Concordance<'a> {
+    lines: Vec<KwicLine<'a>>,
+}
+
+impl<'a> Concordance<'a> {
+    pub fn lines(&self) -> &[KwicLine<'a>] {
+        &self.lines
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Sorts the lines case-insensitively by token forms (or attribute values).
+    /// The sort is stable, lines with equal keys keep their order.
+    pub fn sort(&mut self, key: SortKey) {
+        let values = |tokens: &'a [Token], field: TokenField| {
+            tokens
+                .iter()
+                .map(move |t| t.get(field).unwrap_or_default().to_lowercase())
+        };
+
+        self.lines.sort_by(|a, b| -> Ordering {
+            match key {
+                SortKey::Left => values(a.left, TokenField::Form)
+                    .rev()
+                    .cmp(values(b.left, TokenField::Form).rev()),
+                SortKey::Keyword => {
+                    values(a.keyword, TokenField::Form).cmp(values(b.keyword, TokenField::Form))
+                }
+                SortKey::Right => {
+                    values(a.right, TokenField::Form).cmp(values(b.right, TokenField::Form))
+                }
+                SortKey::Attribute(field) => values(a.keyword, field).cmp(values(b.keyword, field)),
+            }
+        });
+    }
+
+    /// Writes the lines as tab-separated values with a header row.
+    ///
+    /// The columns are `doc_id`, `sentence_id`, `start`, `end`, `left`,
+    /// `keyword` and `right`. Tabs and line breaks inside the text are
+    /// replaced by spaces.
+    ///
+    /// # Errors
+    /// This will return an error if writing into the destination fails.
+    pub fn write_tsv<W: Write>(&self, mut dest: W) -> Result<(), CorporeumError> {
+        let clean = |text: String| text.replace(['\t', '\n', '\r'], " ");
+
+        writeln!(
+            dest,
+            "doc_id\tsentence_id\tstart\tend\tleft\tkeyword\tright"
+        )?;
+        for line in &self.lines {
+            writeln!(
+                dest,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                line.doc_id,
+                line.sentence_id,
+                line.range.start,
+                line.range.end,
+                clean(line.left()),
+                clean(line.keyword()),
+                clean(line.right())
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the lines as an HTML table with the classes `kwic`, `kwic-left`,
+    /// `kwic-keyword` and `kwic-right`.
+    ///
+    /// # Errors
+    /// This will return an error if writing into the destination fails.
+    pub fn write_html<W: Write>(&self, mut dest: W) -> Result<(), CorporeumError> {
+        writeln!(dest, "<table class=\"kwic\">")?;
+        for line in &self.lines {
+            writeln!(
+                dest,
+                "  <tr data-doc=\"{}\" data-sentence=\"{}\"><td class=\"kwic-left\">{}</td><td class=\"kwic-keyword\">{}</td><td class=\"kwic-right\">{}</td></tr>",
+                line.doc_id,
+                line.sentence_id,
+                escape(line.left()),
+                escape(line.keyword()),
+                escape(line.right())
+            )?;
+        }
+        writeln!(dest, "</table>")?;
+
+        Ok(())
+    }
+}
+
+impl Corpus {
+    /// Builds a concordance of query matches, see [`Corpus::query()`].
+    /// Matches that do not belong to this corpus are skipped.
+    pub fn concordance(&self, matches: &[Match], width: ContextWidth) -> Concordance<'_> {
+        let lines = matches
+            .iter()
+            .filter_map(|m| {
+                let sent = self.doc(m.doc_id())?.sentence(m.sentence_id())?;
+                let range = m.range();
+                let keyword = sent.tokens.get(range.clone())?;
+
+                let (left, right) = match width {
+                    ContextWidth::Tokens(n) => (
+                        &sent.tokens[range.start.saturating_sub(n)..range.start],
+                        &sent.tokens[range.end..range.end.saturating_add(n).min(sent.tokens.len())],
+                    ),
+                    ContextWidth::Chars(_) => {
+                        (&sent.tokens[..range.start], &sent.tokens[range.end..])
+                    }
+                };
+
+                Some(KwicLine {
+                    doc_id: m.doc_id(),
+                    sentence_id: m.sentence_id(),
+                    range,
+                    left,
+                    keyword,
+                    right,
+                    width,
+                })
+            })
+            .collect();
+
+        Concordance { lines }
+    }
+}
@@ -3,7 +3,8 @@ use serde_json::from_reader;
 use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 
 use crate::{
-    schema::{Corpus, Document, Metadata},
+    integrity::{free_ids, next_free},
+    schema::{Corpus, Document, Metadata, Sentence, Source},
     CorporeumError,
 };
 
@@ -15,7 +16,7 @@ impl Corpus {
     ///
     /// # Example
     /// ```
-    /// # use corporum::Corpus;
+    /// # use uniform::Corpus;
     /// let corp = Corpus::new();
     /// ```
     ///
@@ -32,7 +33,7 @@ impl Corpus {
     ///
     /// # Example
     /// ```no_run
-    /// # use corporum::Corpus;
+    /// # use uniform::Corpus;
     /// # use std::fs::File;
     /// let file = File::open("some_file.ucf").unwrap();
     /// let corp = match Corpus::load(file) {
@@ -88,7 +89,7 @@ impl Corpus {
     ///
     /// # Example
     /// ```no_run
-    /// # use corporum::Corpus;
+    /// # use uniform::Corpus;
     /// # use std::fs::OpenOptions;
     /// # use std::process::exit;
     /// #
@@ -142,7 +143,7 @@ impl Corpus {
     ///
     /// # Example
     /// ```no_run
-    /// # use corporum::Corpus;
+    /// # use uniform::Corpus;
     /// # use std::fs::OpenOptions;
     /// #
     /// let corp = Corpus::new();
@@ -159,7 +160,7 @@ impl Corpus {
     /// You can also serialize the corpus into an array of bytes.
     /// ## Vectors
     /// ```
-    /// # use corporum::Corpus;
+    /// # use uniform::Corpus;
     /// use std::io::Cursor;
     ///
     /// let corp = Corpus::new();
@@ -171,7 +172,7 @@ impl Corpus {
     /// ```
     /// ## Static arrays
     /// ```
-    /// # use corporum::Corpus;
+    /// # use uniform::Corpus;
     /// let mut buf = [0u8; 256];
     /// let corp = Corpus::new();
     /// // fill the corpus with data...
@@ -232,11 +233,40 @@ impl Corpus {
         self.documents.iter_mut().find(|doc| doc.doc_id() == id)
     }
 
+    /// Returns all sentences of the specified speaker in the corpus,
+    /// together with the ID of the document they belong to.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, Tokenizer};
+    ///
+    /// let mut corp = Corpus::new();
+    /// let id = corp.import_text("Hi. Hello.", &Tokenizer::new("en")).unwrap();
+    /// corp.doc_mut(id).unwrap().sentence_mut(1).unwrap().set_speaker("B");
+    ///
+    /// let by_b: Vec<_> = corp.sentences_by_speaker("B").collect();
+    /// assert_eq!(by_b.len(), 1);
+    /// assert_eq!(by_b[0].1.to_text(), "Hello.");
+    /// ```
+    pub fn sentences_by_speaker<'a>(
+        &'a self,
+        speaker: &'a str,
+    ) -> impl Iterator<Item = (u32, &'a Sentence<Source>)> + 'a {
+        self.documents.iter().flat_map(move |doc| {
+            doc.sentences_by_speaker(speaker)
+                .map(move |sent| (doc.doc_id(), sent))
+        })
+    }
+
     /// Creates a new empty document with a unique ID.
     ///
     /// This new document can then be added to this corpus using [`add_doc`](Self::add_doc).
-    pub fn create_doc(&mut self) -> Document {
-        Document::new(self.documents.last().map_or(0, |doc| doc.id + 1))
+    ///
+    /// # Errors
+    /// This will return an error if a document already has the ID `u32::MAX`.
+    pub fn create_doc(&mut self) -> Result<Document, CorporeumError> {
+        let mut ids = free_ids(self.documents.iter().map(|doc| doc.id).max());
+        Ok(Document::new(next_free(&mut ids, "document")?))
     }
 
     /// Adds the specified document to the corpus.
@@ -262,13 +292,45 @@ impl Corpus {
     /// This will return an error if the corpus does not contain a document
     /// with the specified ID.
     pub fn remove_doc(&mut self, id: u32) -> Result<(), CorporeumError> {
-        if id as usize >= self.documents.len() {
+        let Some(pos) = self.documents.iter().position(|doc| doc.id == id) else {
             return Err(CorporeumError::ElementNotFound(format!(
                 "Document with ID {id} does not exist"
             )));
-        }
+        };
 
-        self.documents.remove(id as usize);
+        self.documents.remove(pos);
         Ok(())
     }
+
+    /// Reassigns contiguous IDs starting at `0` to all documents in corpus
+    /// order and renumbers every document, see [`Document::renumber()`].
+    ///
+    /// Token heads and enhanced dependencies are the only references between
+    /// elements stored in a corpus and they are rewritten; a [`CorpusIndex`](crate::CorpusIndex)
+    /// or query results from before must be built again.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, Tokenizer};
+    ///
+    /// let mut corp = Corpus::new();
+    /// let tokenizer = Tokenizer::new("en");
+    /// for text in ["One.", "Two.", "Three."] {
+    ///     corp.import_text(text, &tokenizer).unwrap();
+    /// }
+    ///
+    /// corp.remove_doc(1).unwrap();
+    /// assert!(corp.remove_doc(1).is_err());
+    /// assert_eq!(corp.doc(2).unwrap().sentences()[0].to_text(), "Three.");
+    ///
+    /// corp.renumber();
+    /// assert_eq!(corp.doc(1).unwrap().sentences()[0].to_text(), "Three.");
+    /// assert!(corp.doc(2).is_none());
+    /// ```
+    pub fn renumber(&mut self) {
+        for (id, doc) in (0..).zip(&mut self.documents) {
+            doc.id = id;
+            doc.renumber();
+        }
+    }
 }
@@ -0,0 +1,165 @@
+use crate::schema::Sentence;
+
+/// Languages written without spaces between words.
+const UNSPACED_LANGS: [&str; 8] = ["zh", "ja", "th", "lo", "km", "my", "bo", "yue"];
+
+/// Tokens attached to the preceding token.
+const NO_SPACE_BEFORE: [&str; 16] = [
+    ".", ",", ";", ":", "!", "?", "%", ")", "]", "}", "…", "...", "”", "’", "»", "‰",
+];
+
+/// Tokens attached to the following token.
+const NO_SPACE_AFTER: [&str; 11] = ["(", "[", "{", "¿", "¡", "“", "‘", "„", "‚", "«", "#"];
+
+/// Currency symbols attached to a following number.
+const CURRENCIES: [&str; 5] = ["$", "£", "€", "¥", "₹"];
+
+/// English contraction suffixes which are split off by most tokenizers.
+const CONTRACTIONS_EN: [&str; 7] = ["'s", "'re", "'ll", "'ve", "'m", "'d", "n't"];
+
+/// Ambiguous quotes which open and close alternately.
+const SYMMETRIC_QUOTES: [&str; 2] = ["\"", "'"];
+
+/// A language-aware, rule-based detokenizer.
+///
+/// Used to turn a sequence of tokens back into text when the tokens carry
+/// no `SpaceAfter` information.
+///
+/// # Example
+/// ```
+/// use uniform::Detokenizer;
+///
+/// let detok = Detokenizer::new("en");
+/// assert_eq!(
+///     detok.detokenize(["He", "said", "\"", "I", "do", "n't", "know", "\"", "."]),
+///     "He said \"I don't know\"."
+/// );
+///
+/// let detok = Detokenizer::new("ja");
+/// assert_eq!(detok.detokenize(["猫", "が", "好き", "。"]), "猫が好き。");
+///
+/// // language codes are case-insensitive
+/// let detok = Detokenizer::new("ZH-Hans");
+/// assert_eq!(detok.detokenize(["我", "喜欢", "猫", "。"]), "我喜欢猫。");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Detokenizer {
+    lang: String,
+}
+
+impl Detokenizer {
+    /// Creates a new detokenizer for the specified language.
+    pub fn new(lang: &str) -> Self {
+        Self {
+            lang: lang.to_owned(),
+        }
+    }
+
+    /// Returns the language this detokenizer was created for.
+    pub fn lang(&self) -> &str {
+        &self.lang
+    }
+
+    /// Decides for each token whether it should be followed by a space.
+    pub fn spaces<'a>(&self, forms: impl IntoIterator<Item = &'a str>) -> Vec<bool> {
+        let forms: Vec<&str> = forms.into_iter().collect();
+        let primary = self
+            .lang
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let unspaced = UNSPACED_LANGS.contains(&primary.as_str());
+        let english = primary == "en";
+        let elides = matches!(primary.as_str(), "fr" | "it" | "ca");
+
+        let mut spaces = vec![true; forms.len()];
+        let mut open_quotes = [false; SYMMETRIC_QUOTES.len()];
+
+        for (i, pair) in forms.windows(2).enumerate() {
+            let (cur, next) = (pair[0], pair[1]);
+
+            // Opening quotes attach to the next token, closing ones to the previous.
+            let cur_quote = SYMMETRIC_QUOTES.iter().position(|&q| q == cur);
+            let cur_opens = cur_quote.is_some_and(|q| {
+                open_quotes[q] = !open_quotes[q];
+                open_quotes[q]
+            });
+            let next_closes = SYMMETRIC_QUOTES
+                .iter()
+                .position(|&q| q == next)
+                .is_some_and(|q| open_quotes[q]);
+
+            let attach = if unspaced {
+                !(is_alphanumeric_word(cur) && is_alphanumeric_word(next))
+            } else {
+                cur_opens
+                    || next_closes
+                    || NO_SPACE_AFTER.contains(&cur)
+                    || NO_SPACE_BEFORE.contains(&next)
+                    || (CURRENCIES.contains(&cur) && next.starts_with(|c: char| c.is_numeric()))
+                    || (english && CONTRACTIONS_EN.contains(&next.to_lowercase().as_str()))
+                    || (elides && cur.len() > 1 && (cur.ends_with('\'') || cur.ends_with('’')))
+            };
+
+            spaces[i] = !attach;
+        }
+
+        spaces
+    }
+
+    /// Joins tokens into natural text.
+    pub fn detokenize<'a>(&self, forms: impl IntoIterator<Item = &'a str>) -> String {
+        let forms: Vec<&str> = forms.into_iter().collect();
+        let spaces = self.spaces(forms.iter().copied());
+
+        let mut text = String::new();
+        for (i, form) in forms.iter().enumerate() {
+            text.push_str(form);
+            if spaces[i] && i + 1 < forms.len() {
+                text.push(' ');
+            }
+        }
+        text
+    }
+}
+
+fn is_alphanumeric_word(form: &str) -> bool {
+    form.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+impl<T> Sentence<T> {
+    /// Returns the text of this sentence.
+    ///
+    /// If any token of the sentence carries `SpaceAfter` information, the
+    /// result is the same as [`to_text()`](Self::to_text). Otherwise the text
+    /// is reconstructed by a [`Detokenizer`](Detokenizer) for the language of
+    /// this sentence.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::Corpus;
+    ///
+    /// let mut corp = Corpus::new();
+    /// let mut doc = corp.create_doc().unwrap();
+    /// let mut sent = doc.create_sentence("en").unwrap();
+    /// for form in ["Hello", ",", "world", "!"] {
+    ///     let tok = sent.create_token(form).unwrap();
+    ///     sent.add_token(tok);
+    /// }
+    ///
+    /// assert_eq!(sent.to_text(), "Hello , world !");
+    /// assert_eq!(sent.detokenize(), "Hello, world!");
+    /// ```
+    pub fn detokenize(&self) -> String {
+        if self
+            .tokens
+            .iter()
+            .any(|t| t.misc_value("SpaceAfter").is_some())
+        {
+            return self.to_text();
+        }
+
+        Detokenizer::new(&self.lang).detokenize(self.tokens.iter().map(|t| t.form()))
+    }
+}
@@ -0,0 +1,951 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    schema::{Corpus, Document, Metadata, Sentence, Source, Target, Token},
+    validate::Location,
+    CorporeumError, TokenField,
+};
+
+/// A property of a document or sentence that is neither a metadata entry nor
+/// one of its children, see [`Change::DocumentChanged`] and [`Change::SentenceChanged`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Property {
+    /// The description of a document.
+    Description,
+    /// The source of a document.
+    Source,
+    /// The language of a sentence, always set.
+    Lang,
+    /// The speaker of a sentence.
+    Speaker,
+}
+
+impl Property {
+    /// Returns the lowercase name of this property, e.g. `"lang"`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Description => "description",
+            Self::Source => "source",
+            Self::Lang => "lang",
+            Self::Speaker => "speaker",
+        }
+    }
+}
+
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A single difference between two versions of a corpus, see [`Corpus::diff()`].
+///
+/// Added and removed elements carry their whole content, so every change
+/// can be applied in both directions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum Change {
+    /// The corpus metadata was added (`old` is `None`), removed (`new` is
+    /// `None`) or changed.
+    CorpusMetadata {
+        old: Option<Metadata>,
+        new: Option<Metadata>,
+    },
+    DocumentAdded {
+        document: Document,
+    },
+    DocumentRemoved {
+        document: Document,
+    },
+    /// A metadata entry of a document was added (`old` is `None`),
+    /// removed (`new` is `None`) or changed.
+    DocumentMeta {
+        doc_id: u32,
+        key: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// The description or source of a document was set, removed or changed.
+    DocumentChanged {
+        doc_id: u32,
+        property: Property,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    SentenceAdded {
+        doc_id: u32,
+        sentence: Sentence<Source>,
+    },
+    SentenceRemoved {
+        doc_id: u32,
+        sentence: Sentence<Source>,
+    },
+    /// A metadata entry of a sentence was added, removed or changed.
+    SentenceMeta {
+        doc_id: u32,
+        sentence_id: u32,
+        key: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// The language or speaker of a sentence was set, removed or changed.
+    SentenceChanged {
+        doc_id: u32,
+        sentence_id: u32,
+        property: Property,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// The start and end offsets of a sentence changed.
+    SentenceTiming {
+        doc_id: u32,
+        sentence_id: u32,
+        old: (Option<u64>, Option<u64>),
+        new: (Option<u64>, Option<u64>),
+    },
+    /// A translation was added to a sentence. A changed translation is
+    /// removed and added again.
+    TranslationAdded {
+        doc_id: u32,
+        sentence_id: u32,
+        translation: Sentence<Target>,
+    },
+    TranslationRemoved {
+        doc_id: u32,
+        sentence_id: u32,
+        translation: Sentence<Target>,
+    },
+    TokenAdded {
+        doc_id: u32,
+        sentence_id: u32,
+        token: Token,
+    },
+    TokenRemoved {
+        doc_id: u32,
+        sentence_id: u32,
+        token: Token,
+    },
+    /// Tokens of a sentence got new IDs (old and new ID) after tokens were
+    /// added or removed before them. Heads and enhanced dependencies pointing
+    /// to the tokens follow them.
+    TokensRenumbered {
+        doc_id: u32,
+        sentence_id: u32,
+        ids: Vec<(u32, u32)>,
+    },
+    /// A token field was set (`old` is `None`), removed (`new` is `None`) or changed.
+    TokenChanged {
+        doc_id: u32,
+        sentence_id: u32,
+        token_id: u32,
+        field: TokenField,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// The start and end offsets of a token changed.
+    TokenTiming {
+        doc_id: u32,
+        sentence_id: u32,
+        token_id: u32,
+        old: (Option<u64>, Option<u64>),
+        new: (Option<u64>, Option<u64>),
+    },
+}
+
+impl Change {
+    /// Returns the corpus, document, sentence or token the change applies to.
+    /// Changes of translations apply to their source sentence.
+    pub const fn location(&self) -> Location {
+        match self {
+            Self::CorpusMetadata { .. } => Location::corpus(),
+            Self::DocumentAdded { document } | Self::DocumentRemoved { document } => {
+                Location::doc(document.id)
+            }
+            Self::DocumentMeta { doc_id, .. } | Self::DocumentChanged { doc_id, .. } => {
+                Location::doc(*doc_id)
+            }
+            Self::SentenceAdded { doc_id, sentence }
+            | Self::SentenceRemoved { doc_id, sentence } => {
+                Location::sentence(*doc_id, sentence.id)
+            }
+            Self::SentenceMeta {
+                doc_id,
+                sentence_id,
+                ..
+            }
+            | Self::SentenceChanged {
+                doc_id,
+                sentence_id,
+                ..
+            }
+            | Self::SentenceTiming {
+                doc_id,
+                sentence_id,
+                ..
+            }
+            | Self::TranslationAdded {
+                doc_id,
+                sentence_id,
+                ..
+            }
+            | Self::TranslationRemoved {
+                doc_id,
+                sentence_id,
+                ..
+            }
+            | Self::TokensRenumbered {
+                doc_id,
+                sentence_id,
+                ..
+            } => Location::sentence(*doc_id, *sentence_id),
+            Self::TokenAdded {
+                doc_id,
+                sentence_id,
+                token,
+            }
+            | Self::TokenRemoved {
+                doc_id,
+                sentence_id,
+                token,
+            } => Location::token(*doc_id, *sentence_id, token.id),
+            Self::TokenChanged {
+                doc_id,
+                sentence_id,
+                token_id,
+                ..
+            }
+            | Self::TokenTiming {
+                doc_id,
+                sentence_id,
+                token_id,
+                ..
+            } => Location::token(*doc_id, *sentence_id, *token_id),
+        }
+    }
+
+    /// Returns the change that undoes this change.
+    pub fn reverse(&self) -> Self {
+        match self.clone() {
+            Self::CorpusMetadata { old, new } => Self::CorpusMetadata { old: new, new: old },
+            Self::DocumentAdded { document } => Self::DocumentRemoved { document },
+            Self::DocumentRemoved { document } => Self::DocumentAdded { document },
+            Self::DocumentMeta {
+                doc_id,
+                key,
+                old,
+                new,
+            } => Self::DocumentMeta {
+                doc_id,
+                key,
+                old: new,
+                new: old,
+            },
+            Self::DocumentChanged {
+                doc_id,
+                property,
+                old,
+                new,
+            } => Self::DocumentChanged {
+                doc_id,
+                property,
+                old: new,
+                new: old,
+            },
+            Self::SentenceAdded { doc_id, sentence } => Self::SentenceRemoved { doc_id, sentence },
+            Self::SentenceRemoved { doc_id, sentence } => Self::SentenceAdded { doc_id, sentence },
+            Self::SentenceMeta {
+                doc_id,
+                sentence_id,
+                key,
+                old,
+                new,
+            } => Self::SentenceMeta {
+                doc_id,
+                sentence_id,
+                key,
+                old: new,
+                new: old,
+            },
+            Self::SentenceChanged {
+                doc_id,
+                sentence_id,
+                property,
+                old,
+                new,
+            } => Self::SentenceChanged {
+                doc_id,
+                sentence_id,
+                property,
+                old: new,
+                new: old,
+            },
+            Self::SentenceTiming {
+                doc_id,
+                sentence_id,
+                old,
+                new,
+            } => Self::SentenceTiming {
+                doc_id,
+                sentence_id,
+                old: new,
+                new: old,
+            },
+            Self::TranslationAdded {
+                doc_id,
+                sentence_id,
+                translation,
+            } => Self::TranslationRemoved {
+                doc_id,
+                sentence_id,
+                translation,
+            },
+            Self::TranslationRemoved {
+                doc_id,
+                sentence_id,
+                translation,
+            } => Self::TranslationAdded {
+                doc_id,
+                sentence_id,
+                translation,
+            },
+            Self::TokenAdded {
+                doc_id,
+                sentence_id,
+                token,
+            } => Self::TokenRemoved {
+                doc_id,
+                sentence_id,
+                token,
+            },
+            Self::TokenRemoved {
+                doc_id,
+                sentence_id,
+                token,
+            } => Self::TokenAdded {
+                doc_id,
+                sentence_id,
+                token,
+            },
+            Self::TokensRenumbered {
+                doc_id,
+                sentence_id,
+                ids,
+            } => Self::TokensRenumbered {
+                doc_id,
+                sentence_id,
+                ids: ids.into_iter().map(|(old, new)| (new, old)).collect(),
+            },
+            Self::TokenChanged {
+                doc_id,
+                sentence_id,
+                token_id,
+                field,
+                old,
+                new,
+            } => Self::TokenChanged {
+                doc_id,
+                sentence_id,
+                token_id,
+                field,
+                old: new,
+                new: old,
+            },
+            Self::TokenTiming {
+                doc_id,
+                sentence_id,
+                token_id,
+                old,
+                new,
+            } => Self::TokenTiming {
+                doc_id,
+                sentence_id,
+                token_id,
+                old: new,
+                new: old,
+            },
+        }
+    }
+}
+
+/// Formats an optional value, `_` (as in CoNLL-U) if it is not set.
+struct Value<'a>(Option<&'a str>);
+
+impl fmt::Display for Value<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(value) => write!(f, "{value:?}"),
+            None => f.write_str("_"),
+        }
+    }
+}
+
+/// Formats start and end offsets as `start-end`, `_` for a missing offset.
+struct Timing((Option<u64>, Option<u64>));
+
+impl fmt::Display for Timing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            (None, None) => f.write_str("_"),
+            (start, end) => {
+                let offset =
+                    |ms: Option<u64>| ms.map_or_else(|| "_".to_owned(), |ms| ms.to_string());
+                write!(f, "{}-{}", offset(start), offset(end))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = self.location();
+        match self {
+            Self::CorpusMetadata { old, new } => {
+                let sign = match (old, new) {
+                    (None, _) => '+',
+                    (_, None) => '-',
+                    _ => '~',
+                };
+                let name = new.as_ref().or(old.as_ref()).map_or("", |meta| &meta.name);
+                write!(f, "{sign} {location}: metadata {name:?}")
+            }
+            Self::DocumentAdded { document } => {
+                write!(f, "+ {location}: {} sentences", document.sentences.len())
+            }
+            Self::DocumentRemoved { document } => {
+                write!(f, "- {location}: {} sentences", document.sentences.len())
+            }
+            Self::SentenceAdded { sentence, .. } => {
+                write!(f, "+ {location}: {}", sentence.to_text())
+            }
+            Self::SentenceRemoved { sentence, .. } => {
+                write!(f, "- {location}: {}", sentence.to_text())
+            }
+            Self::TranslationAdded { translation, .. } => write!(
+                f,
+                "+ {location}: translation {} ({}): {}",
+                translation.id,
+                translation.lang,
+                translation.to_text()
+            ),
+            Self::TranslationRemoved { translation, .. } => write!(
+                f,
+                "- {location}: translation {} ({}): {}",
+                translation.id,
+                translation.lang,
+                translation.to_text()
+            ),
+            Self::TokenAdded { token, .. } => write!(f, "+ {location}: {:?}", token.form),
+            Self::TokenRemoved { token, .. } => write!(f, "- {location}: {:?}", token.form),
+            Self::TokensRenumbered { ids, .. } => {
+                let ids: Vec<String> = ids
+                    .iter()
+                    .map(|(old, new)| format!("{old}->{new}"))
+                    .collect();
+                write!(f, "~ {location}: token IDs {}", ids.join(" "))
+            }
+            Self::DocumentMeta { key, old, new, .. } | Self::SentenceMeta { key, old, new, .. } => {
+                write!(
+                    f,
+                    "~ {location}: meta {key} {} -> {}",
+                    Value(old.as_deref()),
+                    Value(new.as_deref())
+                )
+            }
+            Self::DocumentChanged {
+                property, old, new, ..
+            }
+            | Self::SentenceChanged {
+                property, old, new, ..
+            } => write!(
+                f,
+                "~ {location}: {property} {} -> {}",
+                Value(old.as_deref()),
+                Value(new.as_deref())
+            ),
+            Self::SentenceTiming { old, new, .. } | Self::TokenTiming { old, new, .. } => {
+                write!(
+                    f,
+                    "~ {location}: timing {} -> {}",
+                    Timing(*old),
+                    Timing(*new)
+                )
+            }
+            Self::TokenChanged {
+                field, old, new, ..
+            } => write!(
+                f,
+                "~ {location}: {field} {} -> {}",
+                Value(old.as_deref()),
+                Value(new.as_deref())
+            ),
+        }
+    }
+}
+
+/// The differences between two versions of a corpus, see [`Corpus::diff()`].
+///
+/// The diff is printed as text, one change per line, with
+/// [`Display`](fmt::Display) and as JSON with [`to_json()`](Self::to_json).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CorpusDiff {
+    changes: Vec<Change>,
+}
+
+impl CorpusDiff {
+    /// Returns the changes in corpus order.
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns the diff that undoes this diff, from the newer to the older version.
+    pub fn reverse(&self) -> Self {
+        Self {
+            changes: self.changes.iter().rev().map(Change::reverse).collect(),
+        }
+    }
+
+    /// Serializes the diff as pretty-printed JSON.
+    ///
+    /// # Errors
+    /// This will return an error if serialization fails.
+    pub fn to_json(&self) -> Result<String, CorporeumError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Reads a diff written with [`to_json()`](Self::to_json), e.g. a patch file.
+    ///
+    /// # Errors
+    /// This will return an error if the JSON is not a valid diff.
+    pub fn from_json(json: &str) -> Result<Self, CorporeumError> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+impl fmt::Display for CorpusDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares metadata entries, calling `change` with the key and both values
+/// of every entry that differs.
+fn diff_meta<F: FnMut(String, Option<String>, Option<String>)>(
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+    mut change: F,
+) {
+    let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    for key in keys {
+        let (old, new) = (old.get(key), new.get(key));
+        if old != new {
+            change(key.clone(), old.cloned(), new.cloned());
+        }
+    }
+}
+
+/// Limit of the table used to align tokens of a sentence, larger edits are
+/// compared position by position.
+const MAX_ALIGNMENT_CELLS: usize = 1 << 22;
+
+/// Pairs the tokens of two versions of a sentence by their positions.
+///
+/// If the IDs did not change, tokens are paired by position. Otherwise the
+/// longest common subsequence of the forms is paired, as well as the tokens
+/// between two paired ones if both versions have the same number of them
+/// (tokens whose form changed).
+fn align_tokens(old: &[Token], new: &[Token]) -> Vec<(usize, usize)> {
+    if old.len() == new.len() && old.iter().zip(new).all(|(o, n)| o.id == n.id) {
+        return (0..old.len()).map(|i| (i, i)).collect();
+    }
+
+    let same = |i: usize, j: usize| old[i].form == new[j].form;
+    let prefix = (0..old.len().min(new.len()))
+        .take_while(|&i| same(i, i))
+        .count();
+    let suffix = (0..old.len().min(new.len()) - prefix)
+        .take_while(|&k| same(old.len() - 1 - k, new.len() - 1 - k))
+        .count();
+
+    let mut anchors: Vec<(usize, usize)> = (0..prefix).map(|i| (i, i)).collect();
+    let (rows, cols) = (old.len() - prefix - suffix, new.len() - prefix - suffix);
+    if rows * cols <= MAX_ALIGNMENT_CELLS {
+        // lengths of the longest common subsequences of the remaining tokens
+        let cell = |i: usize, j: usize| i * (cols + 1) + j;
+        let mut lcs = vec![0u32; (rows + 1) * (cols + 1)];
+        for i in (0..rows).rev() {
+            for j in (0..cols).rev() {
+                lcs[cell(i, j)] = if same(prefix + i, prefix + j) {
+                    lcs[cell(i + 1, j + 1)] + 1
+                } else {
+                    lcs[cell(i + 1, j)].max(lcs[cell(i, j + 1)])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < rows && j < cols {
+            if same(prefix + i, prefix + j) {
+                anchors.push((prefix + i, prefix + j));
+                (i, j) = (i + 1, j + 1);
+            } else if lcs[cell(i + 1, j)] >= lcs[cell(i, j + 1)] {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+    }
+    anchors.extend(
+        (0..suffix)
+            .rev()
+            .map(|k| (old.len() - 1 - k, new.len() - 1 - k)),
+    );
+
+    let mut pairs = Vec::with_capacity(anchors.len());
+    let (mut next_old, mut next_new) = (0, 0);
+    for (i, j) in anchors.into_iter().chain([(old.len(), new.len())]) {
+        if i - next_old == j - next_new {
+            pairs.extend((next_old..i).zip(next_new..j));
+        }
+        if i < old.len() {
+            pairs.push((i, j));
+        }
+        (next_old, next_new) = (i + 1, j + 1);
+    }
+    pairs
+}
+
+fn diff_tokens(
+    doc_id: u32,
+    sentence_id: u32,
+    old: &[Token],
+    new: &[Token],
+    changes: &mut Vec<Change>,
+) {
+    let pairs = align_tokens(old, new);
+    let kept: HashSet<u32> = pairs.iter().map(|&(i, _)| old[i].id).collect();
+    let ids: Vec<(u32, u32)> = pairs
+        .iter()
+        .map(|&(i, j)| (old[i].id, new[j].id))
+        .filter(|(old, new)| old != new)
+        .collect();
+    let renumbered: HashMap<u32, u32> = ids.iter().copied().collect();
+
+    let field_changes = |token_id: u32, old: &Token, new: &Token, changes: &mut Vec<Change>| {
+        for field in TokenField::ALL {
+            let (old, new) = (old.get(field), new.get(field));
+            if old != new {
+                changes.push(Change::TokenChanged {
+                    doc_id,
+                    sentence_id,
+                    token_id,
+                    field,
+                    old: old.map(str::to_owned),
+                    new: new.map(str::to_owned),
+                });
+            }
+        }
+    };
+
+    // Links to tokens that are not kept are dropped before renumbering, so
+    // that the renumbering can be reversed exactly.
+    let mut kept_tokens: Vec<Token> = Vec::with_capacity(pairs.len());
+    for &(i, _) in &pairs {
+        let mut tok = old[i].clone();
+        if !ids.is_empty() {
+            tok.remap_heads(|id| kept.contains(&id).then_some(id));
+            field_changes(tok.id, &old[i], &tok, changes);
+        }
+        kept_tokens.push(tok);
+    }
+
+    for tok in old.iter().filter(|tok| !kept.contains(&tok.id)) {
+        changes.push(Change::TokenRemoved {
+            doc_id,
+            sentence_id,
+            token: tok.clone(),
+        });
+    }
+
+    if !ids.is_empty() {
+        changes.push(Change::TokensRenumbered {
+            doc_id,
+            sentence_id,
+            ids,
+        });
+    }
+
+    for (mut tok, &(_, j)) in kept_tokens.into_iter().zip(&pairs) {
+        tok.remap_heads(|id| Some(renumbered.get(&id).copied().unwrap_or(id)));
+        field_changes(new[j].id, &tok, &new[j], changes);
+        let (before, after) = ((tok.start, tok.end), (new[j].start, new[j].end));
+        if before != after {
+            changes.push(Change::TokenTiming {
+                doc_id,
+                sentence_id,
+                token_id: new[j].id,
+                old: before,
+                new: after,
+            });
+        }
+    }
+
+    let paired: HashSet<usize> = pairs.iter().map(|&(_, j)| j).collect();
+    for (_, tok) in new.iter().enumerate().filter(|(j, _)| !paired.contains(j)) {
+        changes.push(Change::TokenAdded {
+            doc_id,
+            sentence_id,
+            token: tok.clone(),
+        });
+    }
+}
+
+fn diff_translations(
+    doc_id: u32,
+    sentence_id: u32,
+    old: &[Sentence<Target>],
+    new: &[Sentence<Target>],
+    changes: &mut Vec<Change>,
+) {
+    let unchanged = |trans: &Sentence<Target>, others: &[Sentence<Target>]| {
+        others.iter().any(|other| other == trans)
+    };
+
+    for trans in old.iter().filter(|trans| !unchanged(trans, new)) {
+        changes.push(Change::TranslationRemoved {
+            doc_id,
+            sentence_id,
+            translation: trans.clone(),
+        });
+    }
+    for trans in new.iter().filter(|trans| !unchanged(trans, old)) {
+        changes.push(Change::TranslationAdded {
+            doc_id,
+            sentence_id,
+            translation: trans.clone(),
+        });
+    }
+}
+
+fn diff_sentence(
+    doc_id: u32,
+    old: &Sentence<Source>,
+    new: &Sentence<Source>,
+    changes: &mut Vec<Change>,
+) {
+    let sentence_id = old.id;
+    diff_meta(&old.meta, &new.meta, |key, old, new| {
+        changes.push(Change::SentenceMeta {
+            doc_id,
+            sentence_id,
+            key,
+            old,
+            new,
+        });
+    });
+
+    for (property, old, new) in [
+        (Property::Lang, Some(&old.lang), Some(&new.lang)),
+        (
+            Property::Speaker,
+            old.speaker.as_ref(),
+            new.speaker.as_ref(),
+        ),
+    ] {
+        if old != new {
+            changes.push(Change::SentenceChanged {
+                doc_id,
+                sentence_id,
+                property,
+                old: old.cloned(),
+                new: new.cloned(),
+            });
+        }
+    }
+    if (old.start, old.end) != (new.start, new.end) {
+        changes.push(Change::SentenceTiming {
+            doc_id,
+            sentence_id,
+            old: (old.start, old.end),
+            new: (new.start, new.end),
+        });
+    }
+
+    diff_translations(
+        doc_id,
+        sentence_id,
+        &old.translations,
+        &new.translations,
+        changes,
+    );
+    diff_tokens(doc_id, sentence_id, &old.tokens, &new.tokens, changes);
+}
+
+fn diff_doc(old: &Document, new: &Document, changes: &mut Vec<Change>) {
+    let doc_id = old.id;
+    diff_meta(&old.meta, &new.meta, |key, old, new| {
+        changes.push(Change::DocumentMeta {
+            doc_id,
+            key,
+            old,
+            new,
+        });
+    });
+
+    for (property, old, new) in [
+        (Property::Description, &old.description, &new.description),
+        (Property::Source, &old.source, &new.source),
+    ] {
+        if old != new {
+            changes.push(Change::DocumentChanged {
+                doc_id,
+                property,
+                old: old.clone(),
+                new: new.clone(),
+            });
+        }
+    }
+
+    let old_sents: HashMap<u32, &Sentence<Source>> =
+        old.sentences.iter().rev().map(|s| (s.id, s)).collect();
+    let new_sents: HashMap<u32, &Sentence<Source>> =
+        new.sentences.iter().rev().map(|s| (s.id, s)).collect();
+
+    for old_sent in &old.sentences {
+        match new_sents.get(&old_sent.id) {
+            Some(new_sent) => diff_sentence(doc_id, old_sent, new_sent, changes),
+            None => changes.push(Change::SentenceRemoved {
+                doc_id,
+                sentence: old_sent.clone(),
+            }),
+        }
+    }
+
+    for new_sent in new
+        .sentences
+        .iter()
+        .filter(|s| !old_sents.contains_key(&s.id))
+    {
+        changes.push(Change::SentenceAdded {
+            doc_id,
+            sentence: new_sent.clone(),
+        });
+    }
+}
+
+impl Corpus {
+    /// Compares this corpus with a newer version of it.
+    ///
+    /// Documents, sentences and translations are matched by their IDs. Tokens
+    /// are aligned by their forms, so that removing or inserting a token is
+    /// reported as such and the following tokens as renumbered, not as changed.
+    /// The diff covers the corpus metadata, the properties and metadata entries
+    /// of documents and sentences, translations, timing and token fields (see
+    /// [`TokenField`]).
+    ///
+    /// Changes follow the order of `self`, elements only found in `newer`
+    /// follow the elements of their parent.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Change, Corpus, TokenField, Tokenizer};
+    ///
+    /// let mut old = Corpus::new();
+    /// old.import_text("The dogs bark.", &Tokenizer::new("en")).unwrap();
+    ///
+    /// let mut new = old.clone();
+    /// let doc = new.doc_mut(0).unwrap();
+    /// doc.set_meta("genre", "fiction");
+    /// let sent = doc.sentence_mut(0).unwrap();
+    /// sent.token_mut(1).unwrap().set(TokenField::Lemma, "dog");
+    /// sent.add_token(sent.create_token("!").unwrap());
+    ///
+    /// let diff = old.diff(&new);
+    /// assert_eq!(
+    ///     diff.to_string(),
+    ///     "~ doc 0: meta genre _ -> \"fiction\"\n\
+    ///      ~ doc 0, sentence 0, token 1: lemma _ -> \"dog\"\n\
+    ///      + doc 0, sentence 0, token 4: \"!\"\n"
+    /// );
+    /// assert!(matches!(diff.changes()[0], Change::DocumentMeta { .. }));
+    /// assert!(diff.to_json().unwrap().contains("\"change\": \"token_added\""));
+    ///
+    /// // removing a token renumbers the following ones
+    /// let mut new = old.clone();
+    /// let sent = new.doc_mut(0).unwrap().sentence_mut(0).unwrap();
+    /// sent.remove_token(0).unwrap();
+    /// sent.set_timing(0, 1500);
+    /// sent.token_mut(1).unwrap().set_timing(500, 900);
+    /// let diff = old.diff(&new);
+    /// assert_eq!(
+    ///     diff.to_string(),
+    ///     "~ doc 0, sentence 0: timing _ -> 0-1500\n\
+    ///      - doc 0, sentence 0, token 0: \"The\"\n\
+    ///      ~ doc 0, sentence 0: token IDs 1->0 2->1 3->2\n\
+    ///      ~ doc 0, sentence 0, token 1: timing _ -> 500-900\n"
+    /// );
+    ///
+    /// let mut patched = old.clone();
+    /// patched.apply(&diff).unwrap();
+    /// assert_eq!(patched, new);
+    ///
+    /// // inserting a token renumbers them the other way
+    /// let mut new = old.clone();
+    /// let sent = new.doc_mut(0).unwrap().sentence_mut(0).unwrap();
+    /// sent.insert_token(0, sent.create_token("All").unwrap()).unwrap();
+    /// sent.token_mut(3).unwrap().set_timing(500, 900);
+    /// let mut patched = old.clone();
+    /// patched.apply(&old.diff(&new)).unwrap();
+    /// assert_eq!(patched, new);
+    /// ```
+    pub fn diff(&self, newer: &Self) -> CorpusDiff {
+        let mut changes = Vec::new();
+        if self.metadata != newer.metadata {
+            changes.push(Change::CorpusMetadata {
+                old: self.metadata.clone(),
+                new: newer.metadata.clone(),
+            });
+        }
+
+        let old_docs: HashMap<u32, &Document> =
+            self.documents.iter().rev().map(|d| (d.id, d)).collect();
+        let new_docs: HashMap<u32, &Document> =
+            newer.documents.iter().rev().map(|d| (d.id, d)).collect();
+
+        for old_doc in &self.documents {
+            match new_docs.get(&old_doc.id) {
+                Some(new_doc) => diff_doc(old_doc, new_doc, &mut changes),
+                None => changes.push(Change::DocumentRemoved {
+                    document: old_doc.clone(),
+                }),
+            }
+        }
+
+        for new_doc in newer
+            .documents
+            .iter()
+            .filter(|d| !old_docs.contains_key(&d.id))
+        {
+            changes.push(Change::DocumentAdded {
+                document: new_doc.clone(),
+            });
+        }
+
+        CorpusDiff { changes }
+    }
+}
@@ -1,4 +1,7 @@
+use std::collections::BTreeMap;
+
 use crate::{
+    integrity::{free_ids, next_free},
     schema::{Document, Sentence, Source},
     CorporeumError,
 };
@@ -9,6 +12,7 @@ impl Document {
             id,
             source: None,
             description: None,
+            meta: BTreeMap::new(),
             sentences: Vec::new(),
         }
     }
@@ -18,21 +22,68 @@ impl Document {
         self.id
     }
 
+    /// Returns the source of this document, e.g. the file it was imported from.
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Sets the source of this document.
+    pub fn set_source(&mut self, source: &str) {
+        self.source = Some(source.to_owned());
+    }
+
+    /// Returns the description of this document.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Sets the description of this document.
+    pub fn set_description(&mut self, desc: &str) {
+        self.description = Some(desc.to_owned());
+    }
+
+    /// Returns the value of a metadata entry of this document.
+    pub fn meta(&self, key: &str) -> Option<&str> {
+        self.meta.get(key).map(String::as_str)
+    }
+
+    /// Returns all metadata entries of this document.
+    pub const fn meta_entries(&self) -> &BTreeMap<String, String> {
+        &self.meta
+    }
+
+    /// Sets a metadata entry of this document, replacing the previous value.
+    pub fn set_meta(&mut self, key: &str, value: &str) {
+        self.meta.insert(key.to_owned(), value.to_owned());
+    }
+
+    /// Removes a metadata entry from this document.
+    pub fn remove_meta(&mut self, key: &str) {
+        self.meta.remove(key);
+    }
+
     /// Creates a new `Sentence` with its language set to the specified language.
     ///
     /// To add this sentence to this document, use [`add_sentence()`](Self::add_sentence).
     ///
     /// # Example
     /// ```no_run
-    /// use corporum::Corpus;
+    /// use uniform::Corpus;
     ///
     /// let mut corp = Corpus::new();
-    /// let mut doc = corp.create_doc();
+    /// let mut doc = corp.create_doc().unwrap();
     ///
-    /// doc.create_sentence("en");
+    /// doc.create_sentence("en").unwrap();
     /// ```
-    pub fn create_sentence(&mut self, lang: &str) -> Sentence<Source> {
-        Sentence::<Source>::new(self.sentences.last().map_or(0, |s| s.id + 1), lang)
+    ///
+    /// # Errors
+    /// This will return an error if a sentence already has the ID `u32::MAX`.
+    pub fn create_sentence(&mut self, lang: &str) -> Result<Sentence<Source>, CorporeumError> {
+        let mut ids = free_ids(self.sentences.iter().map(|s| s.id).max());
+        Ok(Sentence::<Source>::new(
+            next_free(&mut ids, "sentence")?,
+            lang,
+        ))
     }
 
     /// Add a [`Sentence`](crate::Sentence) to the document.
@@ -41,11 +92,11 @@ impl Document {
     ///
     /// # Example
     /// ```no_run
-    /// use corporum::Corpus;
+    /// use uniform::Corpus;
     ///
     /// let mut corp = Corpus::new();
-    /// let mut doc = corp.create_doc();
-    /// let sentence = doc.create_sentence("en");
+    /// let mut doc = corp.create_doc().unwrap();
+    /// let sentence = doc.create_sentence("en").unwrap();
     /// let sentence_id = sentence.sentence_id();
     /// doc.add_sentence(sentence).unwrap_err();
     ///
@@ -69,7 +120,7 @@ impl Document {
     ///
     /// # Example
     /// ```no_run
-    /// use corporum::Corpus;
+    /// use uniform::Corpus;
     /// use std::fs::File;
     ///
     /// let src = File::open("some_file.ucf").unwrap();
@@ -80,22 +131,31 @@ impl Document {
     ///
     /// # Errors
     /// This will return an error if the specified sentence does not exist.
-    pub fn remove_sentence(&mut self, id: usize) -> Result<(), CorporeumError> {
-        if id >= self.sentences.len() {
+    pub fn remove_sentence(&mut self, id: u32) -> Result<(), CorporeumError> {
+        let Some(pos) = self.sentences.iter().position(|sent| sent.id == id) else {
             return Err(CorporeumError::ElementNotFound(format!(
                 "Sentence with ID {id} does not exist"
             )));
-        }
+        };
 
-        self.sentences.remove(id);
+        self.sentences.remove(pos);
         Ok(())
     }
 
+    /// Reassigns contiguous sentence IDs starting at `0` in document order and
+    /// renumbers every sentence, see [`Sentence::renumber()`].
+    pub fn renumber(&mut self) {
+        for (id, sent) in (0..).zip(&mut self.sentences) {
+            sent.id = id;
+            sent.renumber();
+        }
+    }
+
     /// Get a reference to a vector containing all sentences in this `Document`.
     ///
     /// # Example
     /// ```no_run
-    /// use corporum::Corpus;
+    /// use uniform::Corpus;
     /// use std::fs::File;
     ///
     /// let src = File::open("some_file.ucf").unwrap();
@@ -114,10 +174,10 @@ impl Document {
     ///
     //// # Example
     /// ```no_run
-    /// use corporum::Corpus;
+    /// use uniform::Corpus;
     ///
     /// let mut corp = Corpus::new();
-    /// let mut doc = corp.create_doc();
+    /// let mut doc = corp.create_doc().unwrap();
     ///
     /// assert!(doc.sentence(0).is_none());
     /// ```
@@ -125,14 +185,49 @@ impl Document {
         self.sentences.iter().find(|&sent| sent.id == id)
     }
 
+    /// Returns all sentences of the specified speaker.
+    pub fn sentences_by_speaker<'a>(
+        &'a self,
+        speaker: &'a str,
+    ) -> impl Iterator<Item = &'a Sentence<Source>> + 'a {
+        self.sentences
+            .iter()
+            .filter(move |sent| sent.speaker() == Some(speaker))
+    }
+
+    /// Returns all sentences overlapping the time span from `start` to `end`
+    /// (in milliseconds). Sentences without timing are skipped.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, Tokenizer};
+    ///
+    /// let mut corp = Corpus::new();
+    /// let mut doc = corp.create_doc().unwrap();
+    /// doc.add_text("Hi. Hello.", &Tokenizer::new("en")).unwrap();
+    /// doc.sentence_mut(0).unwrap().set_timing(0, 900);
+    /// doc.sentence_mut(1).unwrap().set_timing(1000, 2000);
+    ///
+    /// assert_eq!(doc.sentences_between(500, 1000).count(), 1);
+    /// ```
+    pub fn sentences_between(
+        &self,
+        start: u64,
+        end: u64,
+    ) -> impl Iterator<Item = &Sentence<Source>> + '_ {
+        self.sentences.iter().filter(move |sent| {
+            sent.start().is_some_and(|s| s < end) && sent.end().is_some_and(|e| e > start)
+        })
+    }
+
     /// Fetch a sentence by its `id` and return a reference to it if exists.
     ///
     //// # Example
     /// ```no_run
-    /// use corporum::Corpus;
+    /// use uniform::Corpus;
     ///
     /// let mut corp = Corpus::new();
-    /// let mut doc = corp.create_doc();
+    /// let mut doc = corp.create_doc().unwrap();
     ///
     /// assert!(doc.sentence(0).is_none());
     /// ```
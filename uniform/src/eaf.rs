@@ -0,0 +1,495 @@
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::{BTreeMap, HashMap};
+use std::io::BufRead;
+
+use crate::{
+    schema::{Corpus, Sentence, Source, Token},
+    token::TokenField,
+    xml::attr,
+    CorporeumError, Tokenizer,
+};
+
+/// Sentence metadata key holding the ID of the tier an annotation was read from.
+pub const EAF_TIER: &str = "tier";
+
+/// Constraints of dependent tiers which split a parent annotation into parts.
+const SUBDIVISIONS: [&str; 3] = ["Symbolic_Subdivision", "Time_Subdivision", "Included_In"];
+
+/// Options for [`Corpus::import_eaf()`](crate::Corpus::import_eaf).
+///
+/// Maps dependent tiers of the token tier to [`TokenField`](crate::TokenField)s,
+/// either by their linguistic type or by their tier ID. Tier IDs take precedence.
+///
+/// # Example
+/// ```
+/// use uniform::{EafOptions, TokenField};
+///
+/// let mut options = EafOptions::new();
+/// options.map_type("morph", TokenField::Feats);
+/// options.map_tier("pos@A", TokenField::Xpos);
+///
+/// assert_eq!(options.field("pos@A", "pos"), Some(TokenField::Xpos));
+/// assert_eq!(options.field("pos@B", "pos"), Some(TokenField::Upos));
+/// ```
+#[derive(Debug, Clone)]
+pub struct EafOptions {
+    types: HashMap<String, TokenField>,
+    tiers: HashMap<String, TokenField>,
+}
+
+impl EafOptions {
+    /// Creates options mapping the common linguistic types `lemma`, `pos`,
+    /// `upos`, `xpos`, `feats`, `deprel` and `gloss` to the token fields of the same name.
+    pub fn new() -> Self {
+        let types = [
+            ("lemma", TokenField::Lemma),
+            ("pos", TokenField::Upos),
+            ("upos", TokenField::Upos),
+            ("xpos", TokenField::Xpos),
+            ("feats", TokenField::Feats),
+            ("deprel", TokenField::Deprel),
+            ("gloss", TokenField::Gloss),
+        ];
+
+        Self {
+            types: types
+                .into_iter()
+                .map(|(name, field)| (name.to_owned(), field))
+                .collect(),
+            tiers: HashMap::new(),
+        }
+    }
+
+    /// Maps tiers of the specified linguistic type to a token field.
+    pub fn map_type(&mut self, linguistic_type: &str, field: TokenField) {
+        self.types.insert(linguistic_type.to_owned(), field);
+    }
+
+    /// Maps the tier with the specified ID to a token field.
+    pub fn map_tier(&mut self, tier_id: &str, field: TokenField) {
+        self.tiers.insert(tier_id.to_owned(), field);
+    }
+
+    /// Removes all mappings, including the default ones.
+    pub fn clear(&mut self) {
+        self.types.clear();
+        self.tiers.clear();
+    }
+
+    /// Returns the token field a tier is mapped to.
+    pub fn field(&self, tier_id: &str, linguistic_type: &str) -> Option<TokenField> {
+        self.tiers
+            .get(tier_id)
+            .or_else(|| self.types.get(linguistic_type))
+            .copied()
+    }
+}
+
+impl Default for EafOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+enum Anchor {
+    /// Time-aligned annotation, indices into the time order.
+    Aligned(usize, usize),
+    /// Annotation referring to an annotation of the parent tier.
+    Ref {
+        parent: String,
+        previous: Option<String>,
+    },
+}
+
+struct Annotation {
+    id: String,
+    anchor: Anchor,
+    value: String,
+}
+
+struct Tier {
+    id: String,
+    participant: Option<String>,
+    parent: Option<String>,
+    linguistic_type: String,
+    annotations: Vec<Annotation>,
+}
+
+#[derive(Default)]
+struct Eaf {
+    /// Time values of time slots in document order, `None` for unaligned slots.
+    slots: Vec<Option<u64>>,
+    slot_index: HashMap<String, usize>,
+    /// Constraint of each linguistic type.
+    constraints: HashMap<String, String>,
+    tiers: Vec<Tier>,
+    media: Vec<BTreeMap<&'static str, String>>,
+}
+
+impl Eaf {
+    fn parse<R: BufRead>(src: R) -> Result<Self, CorporeumError> {
+        let mut reader = Reader::from_reader(src);
+        let mut buf = Vec::new();
+
+        let mut eaf = Self::default();
+        let mut pending: Option<Annotation> = None;
+        let mut in_value = false;
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(e) if e.local_name().as_ref() == b"ANNOTATION_VALUE" => {
+                    in_value = true;
+                }
+                Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                    b"MEDIA_DESCRIPTOR" => eaf.add_media(&e)?,
+                    b"TIME_SLOT" => {
+                        let id = attr(&e, "TIME_SLOT_ID")?.unwrap_or_default();
+                        let value = attr(&e, "TIME_VALUE")?.and_then(|v| v.parse().ok());
+                        eaf.slot_index.insert(id, eaf.slots.len());
+                        eaf.slots.push(value);
+                    }
+                    b"TIER" => eaf.tiers.push(Tier {
+                        id: attr(&e, "TIER_ID")?.unwrap_or_default(),
+                        participant: attr(&e, "PARTICIPANT")?.filter(|p| !p.is_empty()),
+                        parent: attr(&e, "PARENT_REF")?,
+                        linguistic_type: attr(&e, "LINGUISTIC_TYPE_REF")?.unwrap_or_default(),
+                        annotations: Vec::new(),
+                    }),
+                    b"ALIGNABLE_ANNOTATION" => {
+                        let slot = |name| -> Result<usize, CorporeumError> {
+                            let id = attr(&e, name)?.unwrap_or_default();
+                            eaf.slot_index.get(&id).copied().ok_or_else(|| {
+                                CorporeumError::ElementNotFound(format!(
+                                    "Time slot '{id}' does not exist"
+                                ))
+                            })
+                        };
+                        pending = Some(Annotation {
+                            id: attr(&e, "ANNOTATION_ID")?.unwrap_or_default(),
+                            anchor: Anchor::Aligned(
+                                slot("TIME_SLOT_REF1")?,
+                                slot("TIME_SLOT_REF2")?,
+                            ),
+                            value: String::new(),
+                        });
+                    }
+                    b"REF_ANNOTATION" => {
+                        pending = Some(Annotation {
+                            id: attr(&e, "ANNOTATION_ID")?.unwrap_or_default(),
+                            anchor: Anchor::Ref {
+                                parent: attr(&e, "ANNOTATION_REF")?.unwrap_or_default(),
+                                previous: attr(&e, "PREVIOUS_ANNOTATION")?,
+                            },
+                            value: String::new(),
+                        });
+                    }
+                    b"LINGUISTIC_TYPE" => {
+                        if let (Some(id), Some(constraint)) =
+                            (attr(&e, "LINGUISTIC_TYPE_ID")?, attr(&e, "CONSTRAINTS")?)
+                        {
+                            eaf.constraints.insert(id, constraint);
+                        }
+                    }
+                    _ => {}
+                },
+                Event::Text(e) if in_value => {
+                    if let Some(annotation) = pending.as_mut() {
+                        annotation.value.push_str(&e.unescape()?);
+                    }
+                }
+                Event::End(e) => match e.local_name().as_ref() {
+                    b"ANNOTATION_VALUE" => in_value = false,
+                    b"ALIGNABLE_ANNOTATION" | b"REF_ANNOTATION" => {
+                        if let (Some(annotation), Some(tier)) =
+                            (pending.take(), eaf.tiers.last_mut())
+                        {
+                            tier.annotations.push(annotation);
+                        }
+                    }
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(eaf)
+    }
+
+    fn add_media(&mut self, elem: &BytesStart) -> Result<(), CorporeumError> {
+        let mut media = BTreeMap::new();
+        for (name, key) in [
+            ("MEDIA_URL", "media_url"),
+            ("RELATIVE_MEDIA_URL", "relative_media_url"),
+            ("MIME_TYPE", "media_mime_type"),
+        ] {
+            if let Some(value) = attr(elem, name)? {
+                media.insert(key, value);
+            }
+        }
+        self.media.push(media);
+        Ok(())
+    }
+
+    fn constraint(&self, tier: &Tier) -> Option<&str> {
+        self.constraints
+            .get(&tier.linguistic_type)
+            .map(String::as_str)
+    }
+
+    fn children<'a>(&'a self, parent: &'a Tier) -> impl Iterator<Item = &'a Tier> + 'a {
+        self.tiers
+            .iter()
+            .filter(move |t| t.parent.as_deref() == Some(parent.id.as_str()))
+    }
+
+    /// Returns the annotations of `tier` belonging to `parent`, in order.
+    fn parts<'a>(&'a self, tier: &'a Tier, parent: &Annotation) -> Vec<&'a Annotation> {
+        match parent.anchor {
+            Anchor::Aligned(start, end) => {
+                let mut parts: Vec<&Annotation> = tier
+                    .annotations
+                    .iter()
+                    .filter(|a| matches!(a.anchor, Anchor::Aligned(s, _) if s >= start && s < end))
+                    .collect();
+                parts.sort_by_key(|a| match a.anchor {
+                    Anchor::Aligned(s, _) => s,
+                    Anchor::Ref { .. } => 0,
+                });
+                parts
+            }
+            Anchor::Ref { .. } => Vec::new(),
+        }
+        .into_iter()
+        .chain(ref_parts(tier, &parent.id))
+        .collect()
+    }
+
+    fn time(&self, slot: usize) -> Option<u64> {
+        self.slots.get(slot).copied().flatten()
+    }
+}
+
+/// Returns the symbolic annotations of `tier` referring to `parent_id`,
+/// following the `PREVIOUS_ANNOTATION` chain.
+fn ref_parts<'a>(tier: &'a Tier, parent_id: &str) -> Vec<&'a Annotation> {
+    let parts: Vec<&Annotation> = tier
+        .annotations
+        .iter()
+        .filter(|a| matches!(&a.anchor, Anchor::Ref { parent, .. } if parent == parent_id))
+        .collect();
+
+    let previous_of = |a: &Annotation| match &a.anchor {
+        Anchor::Ref { previous, .. } => previous.clone(),
+        Anchor::Aligned(..) => None,
+    };
+
+    let mut ordered = Vec::with_capacity(parts.len());
+    let mut current = parts.iter().find(|a| previous_of(a).is_none());
+    while let Some(part) = current {
+        ordered.push(*part);
+        current = parts
+            .iter()
+            .find(|a| previous_of(a).as_deref() == Some(part.id.as_str()));
+        if ordered.len() > parts.len() {
+            break;
+        }
+    }
+
+    // fall back to document order for broken chains
+    if ordered.len() == parts.len() {
+        ordered
+    } else {
+        parts
+    }
+}
+
+impl Corpus {
+    /// Reads an ELAN annotation file (EAF) into a new document and adds it to the corpus.
+    /// Returns the ID of the new document.
+    ///
+    /// The file is mapped as follows:
+    /// - Every annotation of an independent (top-level) tier becomes a sentence
+    ///   with its time span and the tier participant (or the tier ID) as its
+    ///   speaker. The tier ID is stored in sentence metadata under [`EAF_TIER`].
+    ///   Sentences of all tiers are ordered by their start time.
+    /// - If the corpus has [`Metadata`](crate::Metadata), every speaker is
+    ///   registered in it, see [`Metadata::add_speaker()`](crate::Metadata::add_speaker).
+    /// - The first subdividing dependent tier of a sentence tier (`Symbolic_Subdivision`,
+    ///   `Time_Subdivision` or `Included_In`) provides the tokens, time-aligned
+    ///   tokens keep their time span. Without one, the sentence text is split
+    ///   by the tokenizer.
+    /// - Dependent tiers of the token tier are written into token fields as
+    ///   configured by [`EafOptions`](crate::EafOptions).
+    /// - Other dependent tiers of a sentence tier are stored in sentence
+    ///   metadata under their linguistic type.
+    /// - Media descriptors are stored in document metadata under `media_url`,
+    ///   `relative_media_url` and `media_mime_type`, suffixed with `_2`, `_3`, …
+    ///   for additional media files.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, EafOptions, Tokenizer};
+    ///
+    /// let eaf = r#"<ANNOTATION_DOCUMENT>
+    ///   <HEADER><MEDIA_DESCRIPTOR MEDIA_URL="file:///rec.wav" MIME_TYPE="audio/x-wav"/></HEADER>
+    ///   <TIME_ORDER>
+    ///     <TIME_SLOT TIME_SLOT_ID="ts1" TIME_VALUE="0"/>
+    ///     <TIME_SLOT TIME_SLOT_ID="ts2" TIME_VALUE="1200"/>
+    ///   </TIME_ORDER>
+    ///   <TIER TIER_ID="utt@A" PARTICIPANT="A" LINGUISTIC_TYPE_REF="utterance">
+    ///     <ANNOTATION><ALIGNABLE_ANNOTATION ANNOTATION_ID="a1" TIME_SLOT_REF1="ts1" TIME_SLOT_REF2="ts2">
+    ///       <ANNOTATION_VALUE>dogs bark</ANNOTATION_VALUE>
+    ///     </ALIGNABLE_ANNOTATION></ANNOTATION>
+    ///   </TIER>
+    ///   <TIER TIER_ID="words@A" PARENT_REF="utt@A" LINGUISTIC_TYPE_REF="words">
+    ///     <ANNOTATION><REF_ANNOTATION ANNOTATION_ID="a2" ANNOTATION_REF="a1">
+    ///       <ANNOTATION_VALUE>dogs</ANNOTATION_VALUE></REF_ANNOTATION></ANNOTATION>
+    ///     <ANNOTATION><REF_ANNOTATION ANNOTATION_ID="a3" ANNOTATION_REF="a1" PREVIOUS_ANNOTATION="a2">
+    ///       <ANNOTATION_VALUE>bark</ANNOTATION_VALUE></REF_ANNOTATION></ANNOTATION>
+    ///   </TIER>
+    ///   <TIER TIER_ID="lemma@A" PARENT_REF="words@A" LINGUISTIC_TYPE_REF="lemma">
+    ///     <ANNOTATION><REF_ANNOTATION ANNOTATION_ID="a4" ANNOTATION_REF="a2">
+    ///       <ANNOTATION_VALUE>dog</ANNOTATION_VALUE></REF_ANNOTATION></ANNOTATION>
+    ///   </TIER>
+    ///   <LINGUISTIC_TYPE LINGUISTIC_TYPE_ID="utterance" TIME_ALIGNABLE="true"/>
+    ///   <LINGUISTIC_TYPE LINGUISTIC_TYPE_ID="words" CONSTRAINTS="Symbolic_Subdivision"/>
+    ///   <LINGUISTIC_TYPE LINGUISTIC_TYPE_ID="lemma" CONSTRAINTS="Symbolic_Association"/>
+    /// </ANNOTATION_DOCUMENT>"#;
+    ///
+    /// let mut corp = Corpus::new();
+    /// let id = corp
+    ///     .import_eaf(eaf.as_bytes(), &EafOptions::new(), &Tokenizer::new("en"))
+    ///     .unwrap();
+    ///
+    /// let doc = corp.doc(id).unwrap();
+    /// let sent = &doc.sentences()[0];
+    /// assert_eq!(doc.meta("media_url"), Some("file:///rec.wav"));
+    /// assert_eq!(sent.speaker(), Some("A"));
+    /// assert_eq!((sent.start(), sent.end()), (Some(0), Some(1200)));
+    /// assert_eq!(sent.tokens()[0].lemma(), Some("dog"));
+    /// ```
+    ///
+    /// # Errors
+    /// This will return an error if:
+    /// - The XML is malformed
+    /// - An annotation refers to a time slot that does not exist
+    /// - The file contains no annotations
+    pub fn import_eaf<R: BufRead>(
+        &mut self,
+        src: R,
+        options: &EafOptions,
+        tokenizer: &Tokenizer,
+    ) -> Result<u32, CorporeumError> {
+        let eaf = Eaf::parse(src)?;
+
+        let mut doc = self.create_doc()?;
+        for (i, media) in eaf.media.iter().enumerate() {
+            for (key, value) in media {
+                if i == 0 {
+                    doc.set_meta(key, value);
+                } else {
+                    doc.set_meta(&format!("{key}_{}", i + 1), value);
+                }
+            }
+        }
+
+        let mut sentences: Vec<(Option<u64>, Sentence<Source>)> = Vec::new();
+        for tier in eaf.tiers.iter().filter(|t| t.parent.is_none()) {
+            if let Some(meta) = self.metadata.as_mut() {
+                meta.add_speaker(speaker_of(tier));
+            }
+
+            sentences.extend(
+                tier_sentences(&eaf, tier, options, tokenizer)?
+                    .into_iter()
+                    .map(|s| (s.start, s)),
+            );
+        }
+        sentences.sort_by_key(|(start, _)| *start);
+
+        for (i, (_, mut sent)) in sentences.into_iter().enumerate() {
+            sent.id = u32::try_from(i).unwrap_or(u32::MAX);
+            doc.add_sentence(sent)?;
+        }
+
+        let id = doc.doc_id();
+        self.add_doc(doc)?;
+        Ok(id)
+    }
+}
+
+fn speaker_of(tier: &Tier) -> &str {
+    tier.participant.as_deref().unwrap_or(&tier.id)
+}
+
+/// Converts the annotations of an independent tier into sentences.
+fn tier_sentences(
+    eaf: &Eaf,
+    tier: &Tier,
+    options: &EafOptions,
+    tokenizer: &Tokenizer,
+) -> Result<Vec<Sentence<Source>>, CorporeumError> {
+    let subdivides = |t: &&Tier| eaf.constraint(t).is_some_and(|c| SUBDIVISIONS.contains(&c));
+    let token_tier = eaf.children(tier).find(subdivides);
+    let meta_tiers: Vec<&Tier> = eaf.children(tier).filter(|t| !subdivides(t)).collect();
+    let field_tiers: Vec<(&Tier, TokenField)> = token_tier
+        .into_iter()
+        .flat_map(|t| eaf.children(t))
+        .filter_map(|t| Some((t, options.field(&t.id, &t.linguistic_type)?)))
+        .collect();
+
+    let mut sentences = Vec::new();
+    for annotation in &tier.annotations {
+        // the sentences get their IDs once all tiers are read
+        let mut sent = Sentence::<Source>::new(0, tokenizer.lang());
+
+        let words = token_tier
+            .map(|t| eaf.parts(t, annotation))
+            .unwrap_or_default();
+        if words.is_empty() {
+            sent.add_text_tokens(&tokenizer.tokenize(&annotation.value))?;
+        }
+        for (id, word) in (0..).zip(words) {
+            let mut tok = Token::new(id, word.value.trim());
+            if let Anchor::Aligned(start, end) = word.anchor {
+                if let (Some(start), Some(end)) = (eaf.time(start), eaf.time(end)) {
+                    tok.set_timing(start, end);
+                }
+            }
+            for (field_tier, field) in &field_tiers {
+                if let Some(value) = ref_parts(field_tier, &word.id).first() {
+                    tok.set(*field, value.value.trim());
+                }
+            }
+            sent.add_token(tok);
+        }
+        if sent.tokens.is_empty() {
+            continue;
+        }
+
+        if let Anchor::Aligned(start, end) = annotation.anchor {
+            if let (Some(start), Some(end)) = (eaf.time(start), eaf.time(end)) {
+                sent.set_timing(start, end);
+            }
+        }
+        sent.set_meta(EAF_TIER, &tier.id);
+        sent.set_speaker(speaker_of(tier));
+        for meta_tier in &meta_tiers {
+            let values: Vec<&str> = eaf
+                .parts(meta_tier, annotation)
+                .iter()
+                .map(|a| a.value.trim())
+                .collect();
+            if !values.is_empty() {
+                sent.set_meta(&meta_tier.linguistic_type, &values.join(" "));
+            }
+        }
+
+        sentences.push(sent);
+    }
+
+    Ok(sentences)
+}
@@ -0,0 +1,221 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    schema::{Sentence, Token},
+    sentence::join_tokens,
+    CorporeumError,
+};
+
+fn not_found(id: u32) -> CorporeumError {
+    CorporeumError::ElementNotFound(format!("Token with ID {id} does not exist"))
+}
+
+impl<T> Sentence<T> {
+    fn position(&self, id: u32) -> Result<usize, CorporeumError> {
+        self.tokens
+            .iter()
+            .position(|tok| tok.id == id)
+            .ok_or_else(|| not_found(id))
+    }
+
+    /// Replaces the tokens of this sentence, numbering them by position.
+    ///
+    /// Every token comes with its ID before the edit, `None` for new tokens.
+    /// Heads and enhanced dependencies, which all refer to IDs before the edit,
+    /// are rewritten to follow the tokens. `redirect` gives the new target of
+    /// heads pointing to tokens that no longer exist, `None` drops such heads.
+    /// Heads pointing to an ID no token had and heads of a token pointing to
+    /// itself are dropped.
+    pub(crate) fn replace_tokens(
+        &mut self,
+        tokens: Vec<(Option<u32>, Token)>,
+        redirect: &HashMap<u32, Option<u32>>,
+    ) {
+        let mut ids = HashMap::new();
+        self.tokens = Vec::with_capacity(tokens.len());
+        for (id, (old, mut tok)) in (0..).zip(tokens) {
+            if let Some(old) = old {
+                ids.entry(old).or_insert(id);
+            }
+            tok.id = id;
+            self.tokens.push(tok);
+        }
+
+        for tok in &mut self.tokens {
+            let own = tok.id;
+            tok.remap_heads(|id| {
+                let target = redirect.get(&id).copied().unwrap_or(Some(id))?;
+                ids.get(&target).copied().filter(|&new| new != own)
+            });
+        }
+    }
+
+    fn take_tokens(&mut self) -> Vec<(Option<u32>, Token)> {
+        self.tokens
+            .drain(..)
+            .map(|tok| (Some(tok.id), tok))
+            .collect()
+    }
+
+    /// Inserts a token before the token at `index` (or appends it if `index` is
+    /// the number of tokens) and returns its ID.
+    ///
+    /// Token IDs are renumbered by position and heads rewritten to follow the
+    /// tokens. The head of the inserted token refers to the tokens as they were
+    /// before the insertion, like the heads of all other tokens.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, Tokenizer};
+    ///
+    /// let mut corp = Corpus::new();
+    /// corp.import_text("Dogs bark.", &Tokenizer::new("en")).unwrap();
+    /// let sent = corp.doc_mut(0).unwrap().sentence_mut(0).unwrap();
+    ///
+    /// // "loudly" depends on "bark", which is token 1 before the insertion
+    /// let mut tok = sent.create_token("loudly").unwrap();
+    /// tok.set_head("2");
+    /// assert_eq!(sent.insert_token(1, tok).unwrap(), 1);
+    /// assert_eq!(sent.to_text(), "Dogs loudly bark.");
+    /// assert_eq!(sent.tokens()[1].head(), Some("3"));
+    /// ```
+    ///
+    /// # Errors
+    /// This will return an error if:
+    /// - `index` is greater than the number of tokens.
+    /// - The form of the token is empty.
+    pub fn insert_token(&mut self, index: usize, token: Token) -> Result<u32, CorporeumError> {
+        if index > self.tokens.len() {
+            return Err(CorporeumError::BadValue(format!(
+                "Token position {index} is out of range"
+            )));
+        }
+        if token.form.is_empty() {
+            return Err(CorporeumError::EmptyObject(
+                "Token has an empty form".to_owned(),
+            ));
+        }
+
+        let mut tokens = self.take_tokens();
+        tokens.insert(index, (None, token));
+        self.replace_tokens(tokens, &HashMap::new());
+        Ok(self.tokens[index].id)
+    }
+
+    /// Removes a token by its ID and returns it.
+    ///
+    /// The remaining tokens are renumbered by position. Tokens that depended on
+    /// the removed token lose their head and the dependencies on it.
+    ///
+    /// # Errors
+    /// This will return an error if the specified token does not exist.
+    pub fn remove_token(&mut self, id: u32) -> Result<Token, CorporeumError> {
+        let pos = self.position(id)?;
+
+        let mut tokens = self.take_tokens();
+        let (_, removed) = tokens.remove(pos);
+        self.replace_tokens(tokens, &HashMap::from([(id, None)]));
+        Ok(removed)
+    }
+
+    /// Splits a token into several tokens with the given forms, e.g. `"don't"`
+    /// into `"do"` and `"n't"`.
+    ///
+    /// The first part keeps the annotation of the token and the tokens that
+    /// depended on it; the other parts have no annotation. The parts are marked
+    /// with `SpaceAfter=No` except the last, which keeps the spacing of the token.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, Tokenizer};
+    ///
+    /// let mut corp = Corpus::new();
+    /// corp.import_text("I don't know.", &Tokenizer::new("en")).unwrap();
+    /// let sent = corp.doc_mut(0).unwrap().sentence_mut(0).unwrap();
+    ///
+    /// sent.split_token(1, &["do", "n't"]).unwrap();
+    /// assert_eq!(sent.tokens().len(), 5);
+    /// assert_eq!(sent.to_text(), "I don't know.");
+    ///
+    /// sent.merge_tokens(1, 2).unwrap();
+    /// assert_eq!(sent.tokens()[1].form(), "don't");
+    /// ```
+    ///
+    /// # Errors
+    /// This will return an error if:
+    /// - The specified token does not exist.
+    /// - No forms are given or one of them is empty.
+    pub fn split_token(&mut self, id: u32, forms: &[&str]) -> Result<(), CorporeumError> {
+        let pos = self.position(id)?;
+        let Some((first, rest)) = forms.split_first() else {
+            return Err(CorporeumError::EmptyObject(
+                "No forms to split into".to_owned(),
+            ));
+        };
+        if forms.iter().any(|form| form.is_empty()) {
+            return Err(CorporeumError::EmptyObject(
+                "Token has an empty form".to_owned(),
+            ));
+        }
+
+        let mut tokens = self.take_tokens();
+        let space_after = tokens[pos].1.space_after();
+        tokens[pos].1.set_form(first);
+
+        let parts = rest.iter().map(|form| (None, Token::new(0, form)));
+        tokens.splice(pos + 1..pos + 1, parts);
+
+        let last = pos + rest.len();
+        for (_, tok) in &mut tokens[pos..last] {
+            tok.set_space_after(false);
+        }
+        tokens[last].1.set_space_after(space_after);
+
+        self.replace_tokens(tokens, &HashMap::new());
+        Ok(())
+    }
+
+    /// Merges the tokens from `first` to `last` (IDs, inclusive) into a single
+    /// token whose form joins theirs, honoring `SpaceAfter=No`.
+    ///
+    /// The merged token keeps the annotation of the head of the range, the first
+    /// token whose head lies outside of it, and the spacing of the last token.
+    /// Tokens that depended on any merged token depend on the merged token,
+    /// heads and dependencies of the merged token pointing into the range are dropped.
+    ///
+    /// # Errors
+    /// This will return an error if one of the tokens does not exist or
+    /// `last` comes before `first`.
+    pub fn merge_tokens(&mut self, first: u32, last: u32) -> Result<(), CorporeumError> {
+        let (start, end) = (self.position(first)?, self.position(last)?);
+        if end < start {
+            return Err(CorporeumError::BadValue(format!(
+                "Token {last} comes before token {first}"
+            )));
+        }
+
+        let mut tokens = self.take_tokens();
+        let mut merged: Vec<(Option<u32>, Token)> = tokens.drain(start..=end).collect();
+        let ids: HashSet<u32> = merged.iter().filter_map(|(id, _)| *id).collect();
+
+        let form = join_tokens(&merged.iter().map(|(_, tok)| tok).collect::<Vec<_>>());
+        let space_after = merged[merged.len() - 1].1.space_after();
+        let (start_time, end_time) = (merged[0].1.start, merged[merged.len() - 1].1.end);
+        let head = merged
+            .iter()
+            .position(|(_, tok)| tok.head_id().is_none_or(|h| !ids.contains(&h)))
+            .unwrap_or_default();
+
+        let (id, mut tok) = merged.swap_remove(head);
+        tok.form = form;
+        tok.set_space_after(space_after);
+        if let (Some(start), Some(end)) = (start_time, end_time) {
+            tok.set_timing(start, end);
+        }
+
+        let redirect = ids.iter().map(|&other| (other, id)).collect();
+        tokens.insert(start, (id, tok));
+        self.replace_tokens(tokens, &redirect);
+        Ok(())
+    }
+}
@@ -43,4 +43,28 @@ pub enum CorporeumError {
     /// Failed to serialize a value.
     #[error("Bad Value: {0}")]
     BadValue(String),
+
+    /// A query could not be parsed.
+    /// Contains the offset into the query where the error occurred.
+    #[error("Invalid query at offset {0}: {1}")]
+    InvalidQuery(usize, String),
+
+    /// The corpus has structural problems, see
+    /// [`Corpus::check_integrity()`](crate::Corpus::check_integrity).
+    #[error("Integrity: {} structural problems found", .0.len())]
+    Integrity(Vec<crate::Diagnostic>),
+
+    /// Reading an XML document failed.
+    #[error("XML: {0}")]
+    Xml(#[from] quick_xml::Error),
+
+    /// Building an Arrow table failed.
+    #[cfg(feature = "parquet")]
+    #[error("Arrow: {0}")]
+    Arrow(#[from] arrow_schema::ArrowError),
+
+    /// Writing a Parquet file failed.
+    #[cfg(feature = "parquet")]
+    #[error("Parquet: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
 }
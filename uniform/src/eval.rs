@@ -0,0 +1,572 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use crate::{
+    schema::{Corpus, Document, Token},
+    stats::ratio,
+    CorporeumError,
+};
+
+/// Dependency relations of content words, see [`EvalMetric::Clas`].
+const CONTENT_DEPRELS: [&str; 29] = [
+    "nsubj",
+    "obj",
+    "iobj",
+    "csubj",
+    "ccomp",
+    "xcomp",
+    "obl",
+    "vocative",
+    "expl",
+    "dislocated",
+    "advcl",
+    "advmod",
+    "discourse",
+    "nmod",
+    "appos",
+    "nummod",
+    "acl",
+    "amod",
+    "conj",
+    "fixed",
+    "flat",
+    "compound",
+    "list",
+    "parataxis",
+    "orphan",
+    "goeswith",
+    "reparandum",
+    "root",
+    "dep",
+];
+
+/// Dependency relations of function words attached to content words, see [`EvalMetric::Mlas`].
+const FUNCTIONAL_DEPRELS: [&str; 7] = ["aux", "cop", "mark", "det", "clf", "case", "cc"];
+
+/// Features compared by [`EvalMetric::UFeats`].
+const UNIVERSAL_FEATURES: [&str; 21] = [
+    "PronType", "NumType", "Poss", "Reflex", "Foreign", "Abbr", "Gender", "Animacy", "Number",
+    "Case", "Definite", "Degree", "VerbForm", "Mood", "Tense", "Aspect", "Voice", "Evident",
+    "Polarity", "Person", "Polite",
+];
+
+/// A metric of the CoNLL 2018 shared task evaluation, see [`Corpus::evaluate()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EvalMetric {
+    /// Tokens with the same character span.
+    Tokens,
+    /// Sentences with the same character span.
+    Sentences,
+    /// Syntactic words, the same as tokens as uniform has no multiword tokens.
+    Words,
+    Upos,
+    Xpos,
+    /// Universal features.
+    UFeats,
+    /// UPOS, XPOS and universal features.
+    AllTags,
+    Lemmas,
+    /// Unlabeled attachment score.
+    Uas,
+    /// Labeled attachment score, comparing universal dependency relations.
+    Las,
+    /// LAS of content words.
+    Clas,
+    /// Morphology-aware LAS: CLAS with UPOS, universal features and the
+    /// function words attached to the word.
+    Mlas,
+    /// Bilexical dependency score: CLAS with lemmas.
+    Blex,
+}
+
+impl EvalMetric {
+    /// All metrics in the order of the CoNLL 2018 evaluation script.
+    pub const ALL: [Self; 13] = [
+        Self::Tokens,
+        Self::Sentences,
+        Self::Words,
+        Self::Upos,
+        Self::Xpos,
+        Self::UFeats,
+        Self::AllTags,
+        Self::Lemmas,
+        Self::Uas,
+        Self::Las,
+        Self::Clas,
+        Self::Mlas,
+        Self::Blex,
+    ];
+
+    /// Returns the name used by the CoNLL 2018 evaluation script, e.g. `"UFeats"`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Tokens => "Tokens",
+            Self::Sentences => "Sentences",
+            Self::Words => "Words",
+            Self::Upos => "UPOS",
+            Self::Xpos => "XPOS",
+            Self::UFeats => "UFeats",
+            Self::AllTags => "AllTags",
+            Self::Lemmas => "Lemmas",
+            Self::Uas => "UAS",
+            Self::Las => "LAS",
+            Self::Clas => "CLAS",
+            Self::Mlas => "MLAS",
+            Self::Blex => "BLEX",
+        }
+    }
+}
+
+impl fmt::Display for EvalMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Counts of one metric, see [`Evaluation::score()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EvalScore {
+    gold: u64,
+    system: u64,
+    correct: u64,
+    aligned: u64,
+}
+
+impl EvalScore {
+    fn add(&mut self, other: Self) {
+        self.gold += other.gold;
+        self.system += other.system;
+        self.correct += other.correct;
+        self.aligned += other.aligned;
+    }
+
+    /// Returns the number of counted gold elements.
+    pub const fn gold(&self) -> u64 {
+        self.gold
+    }
+
+    /// Returns the number of counted system elements.
+    pub const fn system(&self) -> u64 {
+        self.system
+    }
+
+    /// Returns the number of correct system elements.
+    pub const fn correct(&self) -> u64 {
+        self.correct
+    }
+
+    pub fn precision(&self) -> f64 {
+        ratio(self.correct, self.system)
+    }
+
+    pub fn recall(&self) -> f64 {
+        ratio(self.correct, self.gold)
+    }
+
+    pub fn f1(&self) -> f64 {
+        ratio(2 * self.correct, self.gold + self.system)
+    }
+
+    /// Returns the share of correct elements among the words aligned with the
+    /// gold standard, `None` for tokens, sentences and words or if no words are aligned.
+    pub fn aligned_accuracy(&self) -> Option<f64> {
+        (self.aligned > 0).then(|| ratio(self.correct, self.aligned))
+    }
+}
+
+/// The result of [`Corpus::evaluate()`] for the whole corpus or a single document.
+///
+/// Printed with [`Display`](fmt::Display) as the table of the CoNLL 2018
+/// evaluation script, with scores in percent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Evaluation {
+    scores: BTreeMap<EvalMetric, EvalScore>,
+    documents: BTreeMap<u32, Evaluation>,
+}
+
+impl Evaluation {
+    pub fn score(&self, metric: EvalMetric) -> EvalScore {
+        self.scores.get(&metric).copied().unwrap_or_default()
+    }
+
+    /// Returns the evaluation of a single document, `None` if no document
+    /// with this ID exists in either corpus or this is already a document evaluation.
+    pub fn document(&self, doc_id: u32) -> Option<&Self> {
+        self.documents.get(&doc_id)
+    }
+
+    /// Returns the evaluations of the documents by their IDs.
+    pub fn documents(&self) -> impl Iterator<Item = (u32, &Self)> {
+        self.documents.iter().map(|(&id, eval)| (id, eval))
+    }
+
+    fn add(&mut self, other: &Self) {
+        for (&metric, &score) in &other.scores {
+            self.scores.entry(metric).or_default().add(score);
+        }
+    }
+}
+
+impl fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Metric     | Precision |    Recall |  F1 Score | AligndAcc"
+        )?;
+        writeln!(
+            f,
+            "-----------+-----------+-----------+-----------+-----------"
+        )?;
+        for metric in EvalMetric::ALL {
+            let score = self.score(metric);
+            write!(
+                f,
+                "{:<11}|{:>10.2} |{:>10.2} |{:>10.2} |",
+                metric.name(),
+                100.0 * score.precision(),
+                100.0 * score.recall(),
+                100.0 * score.f1()
+            )?;
+            match score.aligned_accuracy() {
+                Some(accuracy) => writeln!(f, "{:>10.2}", 100.0 * accuracy)?,
+                None => writeln!(f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Where a word is attached in the dependency tree.
+#[derive(Clone, Copy)]
+enum Head {
+    /// The head is `0`.
+    Root,
+    /// The index of the head word.
+    Word(usize),
+    /// The head is not set or does not point to a token of the sentence.
+    Missing,
+}
+
+/// A token with its span of non-whitespace characters in the document.
+struct Word<'a> {
+    tok: &'a Token,
+    span: (usize, usize),
+    head: Head,
+    children: Vec<usize>,
+}
+
+impl Word<'_> {
+    /// Returns the dependency relation without its subtype.
+    fn deprel(&self) -> &str {
+        let deprel = self.tok.deprel().unwrap_or_default();
+        deprel.split(':').next().unwrap_or(deprel)
+    }
+
+    fn is_content(&self) -> bool {
+        CONTENT_DEPRELS.contains(&self.deprel())
+    }
+
+    fn ufeats(&self) -> Vec<&str> {
+        let mut feats: Vec<&str> = self
+            .tok
+            .feats()
+            .unwrap_or_default()
+            .split('|')
+            .filter(|feat| {
+                feat.split_once('=')
+                    .is_some_and(|(name, _)| UNIVERSAL_FEATURES.contains(&name))
+            })
+            .collect();
+        feats.sort_unstable();
+        feats
+    }
+}
+
+/// The words of a document, the character spans of its sentences and its
+/// non-whitespace characters.
+struct Words<'a> {
+    words: Vec<Word<'a>>,
+    sentences: Vec<(usize, usize)>,
+    chars: Vec<char>,
+}
+
+impl<'a> Words<'a> {
+    fn new(doc: Option<&'a Document>) -> Self {
+        let mut words = Vec::new();
+        let mut sentences = Vec::new();
+        let mut chars = Vec::new();
+
+        for sent in doc.iter().flat_map(|doc| &doc.sentences) {
+            let first = words.len();
+            let start = chars.len();
+            let ids: HashMap<u32, usize> = (first..)
+                .zip(&sent.tokens)
+                .map(|(i, tok)| (tok.id, i))
+                .collect();
+
+            for tok in &sent.tokens {
+                let head = match tok.head().map(str::parse::<u32>) {
+                    Some(Ok(0)) => Head::Root,
+                    _ => tok
+                        .head_id()
+                        .and_then(|id| ids.get(&id))
+                        .map_or(Head::Missing, |&i| Head::Word(i)),
+                };
+                let begin = chars.len();
+                chars.extend(tok.form.chars().filter(|c| !c.is_whitespace()));
+                words.push(Word {
+                    tok,
+                    span: (begin, chars.len()),
+                    head,
+                    children: Vec::new(),
+                });
+            }
+
+            if words.len() > first {
+                sentences.push((start, chars.len()));
+            }
+        }
+
+        for i in 0..words.len() {
+            if let Head::Word(parent) = words[i].head {
+                words[parent].children.push(i);
+            }
+        }
+
+        Self {
+            words,
+            sentences,
+            chars,
+        }
+    }
+}
+
+/// Pairs the indices of gold and system words with the same character span.
+fn align(gold: &[Word], system: &[Word]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < gold.len() && j < system.len() {
+        let (g, s) = (gold[i].span, system[j].span);
+        if g == s {
+            pairs.push((i, j));
+        }
+        if g.1 <= s.1 {
+            i += 1;
+        }
+        if s.1 <= g.1 {
+            j += 1;
+        }
+    }
+
+    pairs
+}
+
+fn evaluate_doc(
+    doc_id: u32,
+    gold: Option<&Document>,
+    system: Option<&Document>,
+) -> Result<Evaluation, CorporeumError> {
+    let gold = Words::new(gold);
+    let system = Words::new(system);
+
+    if !gold.words.is_empty() && !system.words.is_empty() {
+        if let Some(pos) = gold
+            .chars
+            .iter()
+            .zip(&system.chars)
+            .position(|(g, s)| g != s)
+            .or_else(|| {
+                (gold.chars.len() != system.chars.len())
+                    .then_some(gold.chars.len().min(system.chars.len()))
+            })
+        {
+            return Err(CorporeumError::BadValue(format!(
+                "The text of document {doc_id} differs from the gold standard at character {pos}"
+            )));
+        }
+    }
+
+    let pairs = align(&gold.words, &system.words);
+    let gold_to_system: HashMap<usize, usize> = pairs.iter().copied().collect();
+    let (g, s) = (&gold.words, &system.words);
+
+    // only an explicit root is credited, not a missing head
+    let same_parent = |gi: usize, si: usize| match (g[gi].head, s[si].head) {
+        (Head::Root, Head::Root) => true,
+        (Head::Word(gp), Head::Word(sp)) => gold_to_system.get(&gp) == Some(&sp),
+        _ => false,
+    };
+    let las = |gi: usize, si: usize| same_parent(gi, si) && g[gi].deprel() == s[si].deprel();
+    let lemma = |gi: usize, si: usize| {
+        g[gi].tok.lemma().is_none_or(|l| l == "_") || g[gi].tok.lemma() == s[si].tok.lemma()
+    };
+    let functional = |words: &[Word], i: usize| -> Vec<(String, Option<String>, Vec<String>)> {
+        words[i]
+            .children
+            .iter()
+            .map(|&c| &words[c])
+            .filter(|c| FUNCTIONAL_DEPRELS.contains(&c.deprel()))
+            .map(|c| {
+                (
+                    c.deprel().to_owned(),
+                    c.tok.upos().map(str::to_owned),
+                    c.ufeats().into_iter().map(str::to_owned).collect(),
+                )
+            })
+            .collect()
+    };
+
+    let score = |content: bool, correct: &dyn Fn(usize, usize) -> bool| {
+        let counted = |w: &Word| !content || w.is_content();
+        let aligned: Vec<&(usize, usize)> =
+            pairs.iter().filter(|(gi, _)| counted(&g[*gi])).collect();
+        EvalScore {
+            gold: g.iter().filter(|w| counted(w)).count() as u64,
+            system: s.iter().filter(|w| counted(w)).count() as u64,
+            correct: aligned.iter().filter(|(gi, si)| correct(*gi, *si)).count() as u64,
+            aligned: aligned.len() as u64,
+        }
+    };
+
+    let tokens = EvalScore {
+        gold: g.len() as u64,
+        system: s.len() as u64,
+        correct: pairs.len() as u64,
+        aligned: 0,
+    };
+    let gold_sentences: HashSet<&(usize, usize)> = gold.sentences.iter().collect();
+    let sentences = EvalScore {
+        gold: gold.sentences.len() as u64,
+        system: system.sentences.len() as u64,
+        correct: system
+            .sentences
+            .iter()
+            .filter(|span| gold_sentences.contains(span))
+            .count() as u64,
+        aligned: 0,
+    };
+
+    let mut scores = BTreeMap::new();
+    scores.insert(EvalMetric::Tokens, tokens);
+    scores.insert(EvalMetric::Sentences, sentences);
+    scores.insert(EvalMetric::Words, tokens);
+    scores.insert(
+        EvalMetric::Upos,
+        score(false, &|gi, si| g[gi].tok.upos() == s[si].tok.upos()),
+    );
+    scores.insert(
+        EvalMetric::Xpos,
+        score(false, &|gi, si| g[gi].tok.xpos() == s[si].tok.xpos()),
+    );
+    scores.insert(
+        EvalMetric::UFeats,
+        score(false, &|gi, si| g[gi].ufeats() == s[si].ufeats()),
+    );
+    scores.insert(
+        EvalMetric::AllTags,
+        score(false, &|gi, si| {
+            g[gi].tok.upos() == s[si].tok.upos()
+                && g[gi].tok.xpos() == s[si].tok.xpos()
+                && g[gi].ufeats() == s[si].ufeats()
+        }),
+    );
+    scores.insert(EvalMetric::Lemmas, score(false, &lemma));
+    scores.insert(EvalMetric::Uas, score(false, &same_parent));
+    scores.insert(EvalMetric::Las, score(false, &las));
+    scores.insert(EvalMetric::Clas, score(true, &las));
+    scores.insert(
+        EvalMetric::Mlas,
+        score(true, &|gi, si| {
+            las(gi, si)
+                && g[gi].tok.upos() == s[si].tok.upos()
+                && g[gi].ufeats() == s[si].ufeats()
+                && functional(g, gi) == functional(s, si)
+        }),
+    );
+    scores.insert(
+        EvalMetric::Blex,
+        score(true, &|gi, si| las(gi, si) && lemma(gi, si)),
+    );
+
+    Ok(Evaluation {
+        scores,
+        documents: BTreeMap::new(),
+    })
+}
+
+impl Corpus {
+    /// Evaluates this corpus, the output of a tagger or parser, against a gold
+    /// standard like the evaluation script of the CoNLL 2018 shared task.
+    ///
+    /// Documents are paired by ID and tokens are aligned by their span of
+    /// non-whitespace characters, so the tokenization may differ but the text
+    /// of paired documents must be the same. A document found in only one of the
+    /// corpora counts as missed or spurious. Heads are read as described in
+    /// [`Token::head_id()`](crate::Token::head_id) and dependency relations
+    /// are compared without subtypes. Gold tokens without a lemma (or `_`)
+    /// accept any lemma.
+    ///
+    /// uniform has no multiword tokens, words are the same as tokens.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, EvalMetric, TokenField, Tokenizer};
+    ///
+    /// let mut gold = Corpus::new();
+    /// gold.import_text("Dogs bark.", &Tokenizer::new("en")).unwrap();
+    /// let mut system = gold.clone();
+    ///
+    /// let annotate = |corp: &mut Corpus, tags: [(&str, &str, &str); 3]| {
+    ///     let sent = corp.doc_mut(0).unwrap().sentence_mut(0).unwrap();
+    ///     for (id, (upos, head, deprel)) in (0..).zip(tags) {
+    ///         let tok = sent.token_mut(id).unwrap();
+    ///         tok.set(TokenField::Upos, upos);
+    ///         tok.set(TokenField::Head, head);
+    ///         tok.set(TokenField::Deprel, deprel);
+    ///     }
+    /// };
+    /// annotate(&mut gold, [("NOUN", "2", "nsubj"), ("VERB", "0", "root"), ("PUNCT", "2", "punct")]);
+    /// annotate(&mut system, [("NOUN", "2", "obj"), ("VERB", "0", "root"), ("PUNCT", "1", "punct")]);
+    ///
+    /// let eval = system.evaluate(&gold).unwrap();
+    /// assert_eq!(eval.score(EvalMetric::Tokens).f1(), 1.0);
+    /// assert_eq!(eval.score(EvalMetric::Upos).f1(), 1.0);
+    /// assert!((eval.score(EvalMetric::Uas).f1() - 2.0 / 3.0).abs() < 1e-9);
+    /// assert!((eval.score(EvalMetric::Las).f1() - 1.0 / 3.0).abs() < 1e-9);
+    /// // punctuation is not a content word
+    /// assert_eq!(eval.score(EvalMetric::Clas).f1(), 0.5);
+    /// assert!(eval.to_string().contains("UAS        |     66.67 |"));
+    /// assert!(eval.document(0).is_some());
+    ///
+    /// // a missing head is not attached to the root
+    /// annotate(&mut system, [("NOUN", "2", "nsubj"), ("VERB", "0", "root"), ("PUNCT", "2", "punct")]);
+    /// let sent = system.doc_mut(0).unwrap().sentence_mut(0).unwrap();
+    /// sent.token_mut(1).unwrap().remove(TokenField::Head);
+    /// let eval = system.evaluate(&gold).unwrap();
+    /// assert!((eval.score(EvalMetric::Uas).f1() - 2.0 / 3.0).abs() < 1e-9);
+    /// ```
+    ///
+    /// # Errors
+    /// This will return an error if the text of a document differs from the
+    /// text of the gold document with the same ID.
+    pub fn evaluate(&self, gold: &Self) -> Result<Evaluation, CorporeumError> {
+        let mut ids: Vec<u32> = gold
+            .documents
+            .iter()
+            .chain(&self.documents)
+            .map(|doc| doc.id)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let mut total = Evaluation::default();
+        for id in ids {
+            let doc = evaluate_doc(id, gold.doc(id), self.doc(id))?;
+            total.add(&doc);
+            total.documents.insert(id, doc);
+        }
+
+        Ok(total)
+    }
+}
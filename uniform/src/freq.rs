@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::io::Write;
+
+use crate::{
+    schema::{Corpus, Document},
+    CorporeumError, TokenField,
+};
+
+/// One row of a [`FrequencyList`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrequencyEntry {
+    values: Vec<String>,
+    freq: u64,
+    doc_freq: u32,
+    per_million: f64,
+}
+
+impl FrequencyEntry {
+    /// Returns the values of the counted fields, in the order of
+    /// [`FrequencyList::fields()`]. Missing values are empty strings.
+    pub fn values(&self) -> &[String] {
+        &self.values
+    }
+
+    /// Returns the number of tokens (or n-grams) with these values.
+    pub const fn freq(&self) -> u64 {
+        self.freq
+    }
+
+    /// Returns the number of documents containing at least one such token.
+    pub const fn doc_freq(&self) -> u32 {
+        self.doc_freq
+    }
+
+    /// Returns the relative frequency per million tokens.
+    pub const fn per_million(&self) -> f64 {
+        self.per_million
+    }
+}
+
+/// Token frequencies of a field or a combination of fields, e.g. `lemma+upos`.
+///
+/// Only tokens of source sentences are counted. Entries are sorted by
+/// descending frequency, ties by their values.
+///
+/// # Example
+/// ```
+/// use uniform::{Corpus, TokenField, Tokenizer};
+///
+/// let mut corp = Corpus::new();
+/// let tokenizer = Tokenizer::new("en");
+/// corp.import_text("The cat saw the dog.", &tokenizer).unwrap();
+/// corp.import_text("A dog barked.", &tokenizer).unwrap();
+///
+/// let freqs = corp.frequency_list(&[TokenField::Form]);
+/// assert_eq!(freqs.total_tokens(), 10);
+///
+/// let dog = freqs.get(&["dog"]).unwrap();
+/// assert_eq!((dog.freq(), dog.doc_freq()), (2, 2));
+/// assert_eq!(freqs.entries()[0].values(), ["."]);
+///
+/// let mut csv = Vec::new();
+/// freqs.write_csv(&mut csv).unwrap();
+/// assert!(String::from_utf8(csv).unwrap().starts_with("form,freq,doc_freq,per_million\n"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrequencyList {
+    fields: Vec<TokenField>,
+    total: u64,
+    docs: u32,
+    entries: Vec<FrequencyEntry>,
+    index: HashMap<Vec<String>, usize>,
+}
+
+impl FrequencyList {
+    /// Counts sequences of `n` consecutive tokens within sentences. With `n = 1`
+    /// all fields are taken from the token, otherwise there is one field per
+    /// token of the sequence.
+    fn build<'a>(
+        fields: &[TokenField],
+        n: usize,
+        docs: impl Iterator<Item = &'a Document>,
+    ) -> Self {
+        let mut counts: HashMap<Vec<String>, (u64, u32)> = HashMap::new();
+        let mut total = 0;
+        let mut doc_count = 0;
+
+        for doc in docs {
+            doc_count += 1;
+            let mut seen: HashMap<Vec<String>, u64> = HashMap::new();
+            for sent in &doc.sentences {
+                for window in sent.tokens.windows(n) {
+                    let key = fields
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &f)| {
+                            let tok = if n == 1 { &window[0] } else { &window[i] };
+                            tok.get(f).unwrap_or_default().to_owned()
+                        })
+                        .collect();
+                    *seen.entry(key).or_default() += 1;
+                    total += 1;
+                }
+            }
+
+            for (key, freq) in seen {
+                let entry = counts.entry(key).or_default();
+                entry.0 += freq;
+                entry.1 += 1;
+            }
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let mut entries: Vec<FrequencyEntry> = counts
+            .into_iter()
+            .map(|(values, (freq, doc_freq))| FrequencyEntry {
+                values,
+                freq,
+                doc_freq,
+                per_million: freq as f64 * 1_000_000.0 / total as f64,
+            })
+            .collect();
+        entries.sort_by(|a, b| b.freq.cmp(&a.freq).then_with(|| a.values.cmp(&b.values)));
+
+        let index = entries
+            .iter()
+            .enumerate()
+            .map(|(i, e)| (e.values.clone(), i))
+            .collect();
+
+        Self {
+            fields: fields.to_vec(),
+            total,
+            docs: doc_count,
+            entries,
+            index,
+        }
+    }
+
+    /// Returns the counted fields. For n-gram lists (see
+    /// [`Corpus::ngrams()`]) this is the counted field repeated `n` times.
+    pub fn fields(&self) -> &[TokenField] {
+        &self.fields
+    }
+
+    /// Returns the number of counted tokens, or n-grams.
+    pub const fn total_tokens(&self) -> u64 {
+        self.total
+    }
+
+    /// Returns the number of counted documents.
+    pub const fn doc_count(&self) -> u32 {
+        self.docs
+    }
+
+    pub fn entries(&self) -> &[FrequencyEntry] {
+        &self.entries
+    }
+
+    /// Returns the entry for the given values, one per counted field.
+    pub fn get(&self, values: &[&str]) -> Option<&FrequencyEntry> {
+        let key: Vec<String> = values.iter().map(|&v| v.to_owned()).collect();
+        self.index.get(&key).map(|&i| &self.entries[i])
+    }
+
+    /// Returns the frequency of the given values, `0` if they do not occur.
+    pub fn freq(&self, values: &[&str]) -> u64 {
+        self.get(values).map_or(0, FrequencyEntry::freq)
+    }
+
+    /// Writes the list as CSV with a header row.
+    ///
+    /// There is one column per counted field followed by `freq`, `doc_freq`
+    /// and `per_million`. Values are quoted when needed.
+    ///
+    /// # Errors
+    /// This will return an error if writing into the destination fails.
+    pub fn write_csv<W: Write>(&self, mut dest: W) -> Result<(), CorporeumError> {
+        for (i, field) in self.fields.iter().enumerate() {
+            if self.fields.len() > 1 && self.fields.iter().all(|f| f == field) {
+                // n-gram columns
+                write!(dest, "{field}_{},", i + 1)?;
+            } else {
+                write!(dest, "{field},")?;
+            }
+        }
+        writeln!(dest, "freq,doc_freq,per_million")?;
+
+        for entry in &self.entries {
+            for value in &entry.values {
+                write!(dest, "{},", csv_escape(value))?;
+            }
+            writeln!(
+                dest,
+                "{},{},{:.2}",
+                entry.freq, entry.doc_freq, entry.per_million
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+impl Corpus {
+    /// Counts the values of the given fields over all documents, see [`FrequencyList`].
+    pub fn frequency_list(&self, fields: &[TokenField]) -> FrequencyList {
+        FrequencyList::build(fields, 1, self.documents.iter())
+    }
+
+    /// Counts the values of the given fields over the documents accepted by `filter`.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, TokenField, Tokenizer};
+    ///
+    /// let mut corp = Corpus::new();
+    /// let tokenizer = Tokenizer::new("en");
+    /// let news = corp.import_text("Stocks fell.", &tokenizer).unwrap();
+    /// corp.doc_mut(news).unwrap().set_meta("genre", "news");
+    /// corp.import_text("Once upon a time.", &tokenizer).unwrap();
+    ///
+    /// let freqs = corp.frequency_list_by(&[TokenField::Form], |doc| doc.meta("genre") == Some("news"));
+    /// assert_eq!((freqs.doc_count(), freqs.total_tokens()), (1, 3));
+    /// ```
+    pub fn frequency_list_by<F: Fn(&Document) -> bool>(
+        &self,
+        fields: &[TokenField],
+        filter: F,
+    ) -> FrequencyList {
+        FrequencyList::build(fields, 1, self.documents.iter().filter(|doc| filter(doc)))
+    }
+
+    /// Counts the n-grams of a field within sentences.
+    ///
+    /// The values of an entry are the field values of the `n` tokens. In CSV
+    /// output the columns are numbered, e.g. `lemma_1,lemma_2`.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, TokenField, Tokenizer};
+    ///
+    /// let mut corp = Corpus::new();
+    /// let tokenizer = Tokenizer::new("en");
+    /// corp.import_text("New York is big. I like New York.", &tokenizer).unwrap();
+    ///
+    /// let bigrams = corp.ngrams(2, TokenField::Form).unwrap();
+    /// assert_eq!(bigrams.freq(&["New", "York"]), 2);
+    /// assert_eq!(bigrams.total_tokens(), 8);
+    /// ```
+    ///
+    /// # Errors
+    /// This will return an error if `n` is `0`.
+    pub fn ngrams(&self, n: usize, field: TokenField) -> Result<FrequencyList, CorporeumError> {
+        if n == 0 {
+            return Err(CorporeumError::BadValue(
+                "N-grams must have at least one token".to_owned(),
+            ));
+        }
+        Ok(FrequencyList::build(
+            &vec![field; n],
+            n,
+            self.documents.iter(),
+        ))
+    }
+}
@@ -0,0 +1,242 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{diff::CorpusDiff, schema::Corpus, CorporeumError};
+
+/// A committed batch of edits, see [`History::commit()`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+    message: String,
+    changes: CorpusDiff,
+}
+
+impl Transaction {
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn changes(&self) -> &CorpusDiff {
+        &self.changes
+    }
+}
+
+/// A corpus with a log of committed transactions that can be undone and redone.
+///
+/// The corpus is edited with the usual methods through
+/// [`corpus_mut()`](Self::corpus_mut). [`commit()`](Self::commit) records the
+/// edits since the last commit as a [`Transaction`], computed with
+/// [`Corpus::diff()`], and [`rollback()`](Self::rollback) discards them.
+///
+/// Undo and redo apply the changes of a transaction (or their reverse) to the
+/// corpus, so every edit [`Corpus::diff()`] covers can be undone: the corpus
+/// metadata, documents, sentences, translations and tokens with all their
+/// properties. A commit checks that its changes reproduce the edited corpus.
+///
+/// # Example
+/// ```
+/// use uniform::{Corpus, History, TokenField, Tokenizer};
+///
+/// let mut corp = Corpus::new();
+/// corp.import_text("The dogs bark.", &Tokenizer::new("en")).unwrap();
+/// let mut history = History::new(corp);
+///
+/// let sent = history.corpus_mut().doc_mut(0).unwrap().sentence_mut(0).unwrap();
+/// sent.token_mut(1).unwrap().set(TokenField::Lemma, "dog");
+/// sent.remove_token(0).unwrap();
+/// history.commit("Lemmatize and drop the article").unwrap();
+/// assert_eq!(history.corpus().doc(0).unwrap().sentences()[0].to_text(), "dogs bark.");
+///
+/// history.undo().unwrap();
+/// let sent = &history.corpus().doc(0).unwrap().sentences()[0];
+/// assert_eq!(sent.to_text(), "The dogs bark.");
+/// assert_eq!(sent.tokens()[1].lemma(), None);
+///
+/// history.redo().unwrap();
+/// assert_eq!(history.corpus().doc(0).unwrap().sentences()[0].tokens()[0].lemma(), Some("dog"));
+/// assert!(history.to_json().unwrap().contains("Lemmatize and drop the article"));
+///
+/// let sent = history.corpus_mut().doc_mut(0).unwrap().sentence_mut(0).unwrap();
+/// let mut trans = sent.create_translation("de").unwrap();
+/// trans.add_token(trans.create_token("Hunde").unwrap());
+/// sent.add_translation(trans);
+/// history.commit("Translate").unwrap();
+/// let sent = history.corpus_mut().doc_mut(0).unwrap().sentence_mut(0).unwrap();
+/// sent.remove_translation(0).unwrap();
+/// assert!(history.commit("Drop the translation").unwrap().is_some());
+///
+/// history.undo().unwrap();
+/// assert_eq!(history.corpus().doc(0).unwrap().sentences()[0].translations().len(), 1);
+///
+/// // removing a token renumbers the following ones
+/// let sent = history.corpus_mut().doc_mut(0).unwrap().sentence_mut(0).unwrap();
+/// sent.remove_token(0).unwrap();
+/// sent.token_mut(0).unwrap().set_timing(500, 900);
+/// history.commit("Drop the subject and time the verb").unwrap();
+/// let sent = &history.corpus().doc(0).unwrap().sentences()[0];
+/// assert_eq!((sent.tokens()[0].form(), sent.tokens()[0].start()), ("bark", Some(500)));
+///
+/// history.undo().unwrap();
+/// let sent = &history.corpus().doc(0).unwrap().sentences()[0];
+/// assert_eq!(sent.to_text(), "dogs bark.");
+/// assert!(sent.tokens().iter().all(|tok| tok.start().is_none()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct History {
+    corpus: Corpus,
+    committed: Corpus,
+    done: Vec<Transaction>,
+    undone: Vec<Transaction>,
+}
+
+impl History {
+    pub fn new(corpus: Corpus) -> Self {
+        Self {
+            committed: corpus.clone(),
+            corpus,
+            done: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    pub fn corpus(&self) -> &Corpus {
+        &self.corpus
+    }
+
+    /// Returns the corpus for editing; the edits are recorded by the next
+    /// [`commit()`](Self::commit).
+    pub fn corpus_mut(&mut self) -> &mut Corpus {
+        &mut self.corpus
+    }
+
+    /// Returns the corpus with uncommitted edits, dropping the history.
+    pub fn into_corpus(self) -> Corpus {
+        self.corpus
+    }
+
+    /// Returns `true` if the corpus was edited since the last commit.
+    pub fn has_changes(&self) -> bool {
+        self.corpus != self.committed
+    }
+
+    /// Records the edits since the last commit as a transaction and returns it,
+    /// `None` if there are no changes to record.
+    ///
+    /// Committing clears the transactions that can be redone.
+    ///
+    /// # Errors
+    /// This will return an error if the changes computed with
+    /// [`Corpus::diff()`] do not reproduce the edited corpus, e.g. because
+    /// IDs are duplicated. Nothing is committed then.
+    pub fn commit(&mut self, message: &str) -> Result<Option<&Transaction>, CorporeumError> {
+        let unrepresentable = || {
+            CorporeumError::BadValue(
+                "The edits cannot be represented as changes of the corpus".to_owned(),
+            )
+        };
+
+        let changes = self.committed.diff(&self.corpus);
+        if changes.is_empty() {
+            return if self.has_changes() {
+                Err(unrepresentable())
+            } else {
+                Ok(None)
+            };
+        }
+
+        self.committed
+            .apply_in_place(&changes)
+            .map_err(|_| unrepresentable())?;
+        if self.committed != self.corpus {
+            self.committed.apply_in_place(&changes.reverse())?;
+            return Err(unrepresentable());
+        }
+
+        self.undone.clear();
+        self.done.push(Transaction {
+            message: message.to_owned(),
+            changes,
+        });
+        Ok(self.done.last())
+    }
+
+    /// Discards the edits since the last commit.
+    pub fn rollback(&mut self) {
+        self.corpus = self.committed.clone();
+    }
+
+    fn ensure_committed(&self) -> Result<(), CorporeumError> {
+        if self.has_changes() {
+            return Err(CorporeumError::BadValue(
+                "The corpus has uncommitted changes".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Undoes the last committed transaction and returns it, `None` if there is
+    /// nothing to undo.
+    ///
+    /// # Errors
+    /// This will return an error if:
+    /// - The corpus has uncommitted changes.
+    /// - The changes of the transaction no longer apply to the corpus.
+    pub fn undo(&mut self) -> Result<Option<&Transaction>, CorporeumError> {
+        self.ensure_committed()?;
+        let Some(transaction) = self.done.pop() else {
+            return Ok(None);
+        };
+
+        let changes = transaction.changes.reverse();
+        if let Err(err) = self.corpus.apply_in_place(&changes) {
+            self.done.push(transaction);
+            return Err(err);
+        }
+        // the committed corpus is equal to the corpus, so the changes fit it
+        self.committed.apply_in_place(&changes)?;
+        self.undone.push(transaction);
+        Ok(self.undone.last())
+    }
+
+    /// Redoes the last undone transaction and returns it, `None` if there is
+    /// nothing to redo.
+    ///
+    /// # Errors
+    /// This will return an error if:
+    /// - The corpus has uncommitted changes.
+    /// - The changes of the transaction no longer apply to the corpus.
+    pub fn redo(&mut self) -> Result<Option<&Transaction>, CorporeumError> {
+        self.ensure_committed()?;
+        let Some(transaction) = self.undone.pop() else {
+            return Ok(None);
+        };
+
+        if let Err(err) = self.corpus.apply_in_place(&transaction.changes) {
+            self.undone.push(transaction);
+            return Err(err);
+        }
+        self.committed.apply_in_place(&transaction.changes)?;
+        self.done.push(transaction);
+        Ok(self.done.last())
+    }
+
+    /// Returns the committed transactions in order, without undone ones.
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.done
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Serializes the committed transactions as pretty-printed JSON,
+    /// e.g. for auditing.
+    ///
+    /// # Errors
+    /// This will return an error if serialization fails.
+    pub fn to_json(&self) -> Result<String, CorporeumError> {
+        Ok(serde_json::to_string_pretty(&self.done)?)
+    }
+}
@@ -0,0 +1,277 @@
+use std::fmt::Write as _;
+use std::io::Write;
+
+use crate::{
+    schema::{Corpus, Sentence, Source, Token},
+    CorporeumError,
+};
+
+/// `misc` key holding the morpheme-segmented form of a token, e.g. `MSeg=abur-u-n`.
+pub const MORPHEME_SEGMENTATION: &str = "MSeg";
+
+/// Output format of interlinear glossed text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IgtFormat {
+    /// Plain text with the form and gloss lines aligned by padding.
+    Text,
+    /// HTML with one inline block per word.
+    Html,
+    /// LaTeX using the `gb4e` package.
+    Gb4e,
+    /// LaTeX using the `expex` package.
+    Expex,
+}
+
+/// Returns the form of a token as written on the first line, i.e. its
+/// morpheme segmentation if available.
+fn igt_form(tok: &Token) -> &str {
+    tok.misc_value(MORPHEME_SEGMENTATION)
+        .unwrap_or_else(|| tok.form())
+}
+
+/// Splits a gloss into parts, marking grammatical category labels
+/// (runs of uppercase letters and digits such as `OBL` or `3SG`).
+fn categories(gloss: &str) -> Vec<(bool, &str)> {
+    let is_label = |c: char| c.is_uppercase() || c.is_ascii_digit();
+
+    let mut parts: Vec<(bool, &str)> = Vec::new();
+    let mut start = 0;
+    for (i, c) in gloss.char_indices().skip(1) {
+        let prev = gloss[..i].chars().next_back().is_some_and(is_label);
+        if prev != is_label(c) {
+            parts.push((prev, &gloss[start..i]));
+            start = i;
+        }
+    }
+    if start < gloss.len() {
+        parts.push((
+            gloss[start..].chars().next().is_some_and(is_label),
+            &gloss[start..],
+        ));
+    }
+
+    // a single capital letter is not a label, e.g. proper names
+    parts
+        .into_iter()
+        .map(|(label, part)| (label && part.chars().count() > 1, part))
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn latex_gloss(gloss: &str) -> String {
+    if gloss.is_empty() {
+        return "{}".to_owned();
+    }
+
+    categories(gloss)
+        .into_iter()
+        .map(|(label, part)| {
+            if label {
+                format!("\\textsc{{{}}}", escape_latex(&part.to_lowercase()))
+            } else {
+                escape_latex(part)
+            }
+        })
+        .collect()
+}
+
+fn html_gloss(gloss: &str) -> String {
+    categories(gloss)
+        .into_iter()
+        .map(|(label, part)| {
+            if label {
+                format!("<abbr>{}</abbr>", escape_html(part))
+            } else {
+                escape_html(part)
+            }
+        })
+        .collect()
+}
+
+fn latex_words(words: &[&str]) -> String {
+    words
+        .iter()
+        .map(|w| {
+            if w.contains(' ') {
+                format!("{{{w}}}")
+            } else {
+                (*w).to_owned()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+impl Sentence<Source> {
+    /// Renders this sentence as interlinear glossed text in Leipzig style.
+    ///
+    /// The first line contains the token forms (or their morpheme segmentation
+    /// from the [`MORPHEME_SEGMENTATION`] `misc` entry), the second line the
+    /// [`gloss`](crate::Token::gloss) of every token and the third line the
+    /// first translation of the sentence as a free translation. Category labels
+    /// in glosses are set in small caps in LaTeX and wrapped in `<abbr>` in HTML.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, IgtFormat};
+    ///
+    /// let mut corp = Corpus::new();
+    /// let mut doc = corp.create_doc().unwrap();
+    /// let mut sent = doc.create_sentence("lez").unwrap();
+    /// for (form, gloss) in [("Gila", "now"), ("aburun", "they-OBL-GEN"), ("ferma", "farm")] {
+    ///     let mut tok = sent.create_token(form).unwrap();
+    ///     tok.set_gloss(gloss);
+    ///     if form == "aburun" {
+    ///         tok.set_misc_value("MSeg", "abur-u-n");
+    ///     }
+    ///     sent.add_token(tok);
+    /// }
+    ///
+    /// let mut trans = sent.create_translation("en").unwrap();
+    /// for form in ["Now", "their", "farm"] {
+    ///     let tok = trans.create_token(form).unwrap();
+    ///     trans.add_token(tok);
+    /// }
+    /// sent.add_translation(trans);
+    ///
+    /// assert_eq!(
+    ///     sent.to_igt(IgtFormat::Text),
+    ///     "Gila abur-u-n     ferma\nnow  they-OBL-GEN farm\n'Now their farm'\n"
+    /// );
+    /// assert!(sent.to_igt(IgtFormat::Gb4e).contains("they-\\textsc{obl}-\\textsc{gen}"));
+    /// ```
+    pub fn to_igt(&self, format: IgtFormat) -> String {
+        let forms: Vec<&str> = self.tokens.iter().map(igt_form).collect();
+        let glosses: Vec<&str> = self
+            .tokens
+            .iter()
+            .map(|t| t.gloss().unwrap_or_default())
+            .collect();
+        let translation = self.translations.first().map(Sentence::detokenize);
+
+        let mut out = String::new();
+        match format {
+            IgtFormat::Text => {
+                let widths: Vec<usize> = forms
+                    .iter()
+                    .zip(&glosses)
+                    .map(|(f, g)| f.chars().count().max(g.chars().count()))
+                    .collect();
+                for line in [&forms, &glosses] {
+                    let padded: Vec<String> = line
+                        .iter()
+                        .zip(&widths)
+                        .map(|(word, &width)| format!("{word:width$}"))
+                        .collect();
+                    let _ = writeln!(out, "{}", padded.join(" ").trim_end());
+                }
+                if let Some(translation) = translation {
+                    let _ = writeln!(out, "'{translation}'");
+                }
+            }
+            IgtFormat::Html => {
+                out.push_str("<div class=\"igt\">\n  <div class=\"igt-words\">\n");
+                for (form, gloss) in forms.iter().zip(&glosses) {
+                    let _ = writeln!(
+                        out,
+                        "    <div class=\"igt-word\"><span class=\"igt-form\">{}</span><span class=\"igt-gloss\">{}</span></div>",
+                        escape_html(form),
+                        html_gloss(gloss)
+                    );
+                }
+                out.push_str("  </div>\n");
+                if let Some(translation) = translation {
+                    let _ = writeln!(
+                        out,
+                        "  <p class=\"igt-translation\">‘{}’</p>",
+                        escape_html(&translation)
+                    );
+                }
+                out.push_str("</div>\n");
+            }
+            IgtFormat::Gb4e => {
+                let forms: Vec<String> = forms.iter().map(|f| escape_latex(f)).collect();
+                let glosses: Vec<String> = glosses.iter().map(|g| latex_gloss(g)).collect();
+
+                out.push_str("\\begin{exe}\n\\ex\n");
+                let _ = writeln!(out, "\\gll {}\\\\", latex_words(&to_refs(&forms)));
+                let _ = writeln!(out, "{}\\\\", latex_words(&to_refs(&glosses)));
+                if let Some(translation) = translation {
+                    let _ = writeln!(out, "\\trans `{}'", escape_latex(&translation));
+                }
+                out.push_str("\\end{exe}\n");
+            }
+            IgtFormat::Expex => {
+                let forms: Vec<String> = forms.iter().map(|f| escape_latex(f)).collect();
+                let glosses: Vec<String> = glosses.iter().map(|g| latex_gloss(g)).collect();
+
+                out.push_str("\\ex\n\\begingl\n");
+                let _ = writeln!(out, "\\gla {}//", latex_words(&to_refs(&forms)));
+                let _ = writeln!(out, "\\glb {}//", latex_words(&to_refs(&glosses)));
+                if let Some(translation) = translation {
+                    let _ = writeln!(out, "\\glft `{}'//", escape_latex(&translation));
+                }
+                out.push_str("\\endgl\n\\xe\n");
+            }
+        }
+
+        out
+    }
+}
+
+fn to_refs(words: &[String]) -> Vec<&str> {
+    words.iter().map(String::as_str).collect()
+}
+
+impl Corpus {
+    /// Writes all sentences of the corpus as interlinear glossed text,
+    /// see [`Sentence::to_igt()`](crate::Sentence::to_igt).
+    ///
+    /// Examples are separated by an empty line. No document preamble is
+    /// written, LaTeX output is meant to be included into a document
+    /// loading `gb4e` or `expex`.
+    ///
+    /// # Errors
+    /// This will return an error if writing into the destination fails.
+    pub fn export_igt<W: Write>(
+        &self,
+        mut dest: W,
+        format: IgtFormat,
+    ) -> Result<(), CorporeumError> {
+        let mut first = true;
+        for doc in &self.documents {
+            for sent in &doc.sentences {
+                if !first {
+                    writeln!(dest)?;
+                }
+                first = false;
+                dest.write_all(sent.to_igt(format).as_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+}
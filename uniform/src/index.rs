@@ -0,0 +1,288 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{Read, Write};
+
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    query::{Condition, Match, Query},
+    schema::{Corpus, Document, Sentence, Source},
+    CorporeumError, TokenField,
+};
+
+/// Position of a token: document ID, sentence ID and the index of the token
+/// in [`Sentence::tokens()`](crate::Sentence::tokens).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Position(u32, u32, u32);
+
+impl Position {
+    pub const fn doc_id(&self) -> u32 {
+        self.0
+    }
+
+    pub const fn sentence_id(&self) -> u32 {
+        self.1
+    }
+
+    pub const fn token(&self) -> usize {
+        self.2 as usize
+    }
+}
+
+/// Location of a document and its sentences in the corpus vectors.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DocEntry {
+    position: usize,
+    sentences: HashMap<u32, usize>,
+}
+
+/// An inverted index of a corpus mapping token field values to token positions.
+///
+/// The index also maps document and sentence IDs to their positions, so that
+/// lookups do not need to scan the corpus. It does not borrow the corpus and
+/// can be saved next to it and loaded in a later session; an index is only
+/// meaningful for the exact corpus it was built from, which
+/// [`is_valid_for()`](Self::is_valid_for) checks cheaply.
+///
+/// # Example
+/// ```
+/// use uniform::{Corpus, CorpusIndex, Query, TokenField, Tokenizer};
+///
+/// let mut corp = Corpus::new();
+/// let tokenizer = Tokenizer::new("en");
+/// corp.import_text("The dog barked. A cat slept. The dog slept.", &tokenizer).unwrap();
+///
+/// let index = CorpusIndex::build(&corp, &[TokenField::Form, TokenField::Lemma]);
+/// assert_eq!(index.lookup(TokenField::Form, "dog").len(), 2);
+///
+/// let mut saved = Vec::new();
+/// index.save_into(&mut saved).unwrap();
+/// let index = CorpusIndex::load(saved.as_slice()).unwrap();
+/// assert!(index.is_valid_for(&corp));
+///
+/// let query = Query::parse(r#""dog" "slept""#).unwrap();
+/// assert_eq!(corp.query_indexed(&query, &index).unwrap(), corp.query(&query));
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CorpusIndex {
+    postings: BTreeMap<TokenField, HashMap<String, Vec<Position>>>,
+    docs: HashMap<u32, DocEntry>,
+    doc_count: usize,
+    token_count: usize,
+}
+
+impl CorpusIndex {
+    /// Builds an index of the given fields over the source sentences of the corpus.
+    pub fn build(corpus: &Corpus, fields: &[TokenField]) -> Self {
+        let mut index = Self {
+            postings: fields.iter().map(|&f| (f, HashMap::new())).collect(),
+            doc_count: corpus.documents.len(),
+            ..Self::default()
+        };
+
+        for (doc_pos, doc) in corpus.documents.iter().enumerate() {
+            let entry = index.docs.entry(doc.id).or_default();
+            entry.position = doc_pos;
+
+            for (sent_pos, sent) in doc.sentences.iter().enumerate() {
+                entry.sentences.insert(sent.id, sent_pos);
+
+                for (i, tok) in sent.tokens.iter().enumerate() {
+                    let position = Position(doc.id, sent.id, u32::try_from(i).unwrap_or(u32::MAX));
+                    for (field, values) in &mut index.postings {
+                        if let Some(value) = tok.get(*field) {
+                            values.entry(value.to_owned()).or_default().push(position);
+                        }
+                    }
+                    index.token_count += 1;
+                }
+            }
+        }
+
+        index
+    }
+
+    /// Returns the indexed fields.
+    pub fn fields(&self) -> impl Iterator<Item = TokenField> + '_ {
+        self.postings.keys().copied()
+    }
+
+    /// Returns the positions of all tokens whose field has exactly this value,
+    /// in corpus order. Returns an empty slice if the field is not indexed.
+    pub fn lookup(&self, field: TokenField, value: &str) -> &[Position] {
+        self.postings
+            .get(&field)
+            .and_then(|values| values.get(value))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns all distinct values of an indexed field.
+    pub fn values(&self, field: TokenField) -> impl Iterator<Item = &str> {
+        self.postings
+            .get(&field)
+            .into_iter()
+            .flat_map(|values| values.keys().map(String::as_str))
+    }
+
+    /// Returns `true` if the index was built from a corpus with the same
+    /// documents and number of tokens. This does not detect edits that keep
+    /// the number of tokens; rebuild the index after modifying the corpus.
+    pub fn is_valid_for(&self, corpus: &Corpus) -> bool {
+        self.doc_count == corpus.documents.len()
+            && corpus
+                .documents
+                .iter()
+                .enumerate()
+                .all(|(i, doc)| self.docs.get(&doc.id).is_some_and(|e| e.position == i))
+            && self.token_count
+                == corpus
+                    .documents
+                    .iter()
+                    .flat_map(|d| &d.sentences)
+                    .map(|s| s.tokens.len())
+                    .sum::<usize>()
+    }
+
+    /// Returns the document with the specified ID without scanning the corpus.
+    pub fn doc<'a>(&self, corpus: &'a Corpus, id: u32) -> Option<&'a Document> {
+        corpus
+            .documents
+            .get(self.docs.get(&id)?.position)
+            .filter(|doc| doc.id == id)
+    }
+
+    /// Returns the sentence with the specified IDs without scanning the corpus.
+    pub fn sentence<'a>(
+        &self,
+        corpus: &'a Corpus,
+        doc_id: u32,
+        sentence_id: u32,
+    ) -> Option<&'a Sentence<Source>> {
+        let entry = self.docs.get(&doc_id)?;
+        self.doc(corpus, doc_id)?
+            .sentences
+            .get(*entry.sentences.get(&sentence_id)?)
+            .filter(|sent| sent.id == sentence_id)
+    }
+
+    /// Returns the sentences containing a token that satisfies the condition,
+    /// `None` if this cannot be answered from the indexed fields.
+    fn candidates(&self, cond: &Condition) -> Option<BTreeSet<(u32, u32)>> {
+        match cond {
+            Condition::Attr(field, re, false) => {
+                let values = self.postings.get(field)?;
+                // an empty value also matches tokens without the field, which are not indexed
+                if re.is_match("") {
+                    return None;
+                }
+                Some(
+                    values
+                        .iter()
+                        .filter(|(value, _)| re.is_match(value))
+                        .flat_map(|(_, positions)| positions)
+                        .map(|p| (p.0, p.1))
+                        .collect(),
+                )
+            }
+            Condition::And(conds) => conds
+                .iter()
+                .filter_map(|c| self.candidates(c))
+                .reduce(|a, b| a.intersection(&b).copied().collect()),
+            Condition::Or(conds) => conds
+                .iter()
+                .map(|c| self.candidates(c))
+                .collect::<Option<Vec<_>>>()
+                .map(|sets| sets.into_iter().flatten().collect()),
+            _ => None,
+        }
+    }
+
+    /// Serializes the index as zlib-compressed JSON.
+    ///
+    /// # Errors
+    /// This will return an error if:
+    /// - The serialization fails
+    /// - Compression fails
+    pub fn save_into<W: Write>(&self, dest: W) -> Result<(), CorporeumError> {
+        let serialized = serde_json::ser::to_vec(&self)?;
+
+        let mut compressor = ZlibEncoder::new(dest, Compression::default());
+        compressor
+            .write_all(&serialized)
+            .map_err(CorporeumError::CompressionError)?;
+
+        Ok(())
+    }
+
+    /// Loads an index saved with [`save_into()`](Self::save_into).
+    ///
+    /// # Errors
+    /// This will return an error if:
+    /// - The contents could not be decompressed.
+    /// - The contents could not be deserialized.
+    pub fn load<R: Read>(source: R) -> Result<Self, CorporeumError> {
+        let mut decompressed = Vec::new();
+        ZlibDecoder::new(source)
+            .read_to_end(&mut decompressed)
+            .map_err(CorporeumError::DecompressionError)?;
+
+        Ok(serde_json::from_slice(&decompressed)?)
+    }
+}
+
+impl Corpus {
+    /// Builds an index of token forms, lemmas and UPOS tags,
+    /// see [`CorpusIndex::build()`].
+    pub fn build_index(&self) -> CorpusIndex {
+        CorpusIndex::build(
+            self,
+            &[TokenField::Form, TokenField::Lemma, TokenField::Upos],
+        )
+    }
+
+    /// Finds all matches of a query like [`query()`](Self::query), using the
+    /// index to skip sentences that cannot match.
+    ///
+    /// Only the first token pattern of the query is looked up in the index. If it
+    /// does not constrain an indexed field, all sentences are searched.
+    ///
+    /// # Errors
+    /// This will return [`CorporeumError::BadValue`] if the index was not built
+    /// from this corpus, see [`CorpusIndex::is_valid_for()`].
+    pub fn query_indexed(
+        &self,
+        query: &Query,
+        index: &CorpusIndex,
+    ) -> Result<Vec<Match>, CorporeumError> {
+        if !index.is_valid_for(self) {
+            return Err(CorporeumError::BadValue(
+                "The index does not belong to this corpus".to_owned(),
+            ));
+        }
+
+        let Some(candidates) = query.first_condition().and_then(|c| index.candidates(c)) else {
+            return Ok(self.query(query));
+        };
+
+        let mut matches = Vec::new();
+        for (doc_id, sentence_id) in candidates {
+            let Some(sent) = index.sentence(self, doc_id, sentence_id) else {
+                continue;
+            };
+            matches.extend(
+                query
+                    .find_in(sent)
+                    .into_iter()
+                    .map(|range| Match::new(doc_id, sentence_id, range)),
+            );
+        }
+
+        // candidates are ordered by IDs, restore corpus order
+        let order = |m: &Match| {
+            let entry = &index.docs[&m.doc_id()];
+            (entry.position, entry.sentences[&m.sentence_id()])
+        };
+        matches.sort_by_key(|m| (order(m), m.range().start));
+        Ok(matches)
+    }
+}
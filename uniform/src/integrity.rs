@@ -0,0 +1,378 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek, Write};
+
+use crate::{
+    schema::{Corpus, Document, Sentence, Source, Token},
+    validate::{Diagnostic, Location, Severity},
+    CorporeumError,
+};
+
+/// What [`Corpus::load_with()`] does with a corpus that fails
+/// [`Corpus::check_integrity()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrityMode {
+    /// Fail with [`CorporeumError::Integrity`].
+    Reject,
+    /// Fix the problems with [`Corpus::repair()`].
+    Repair,
+}
+
+/// The sentence or translation whose tokens are checked.
+#[derive(Clone, Copy)]
+struct Owner {
+    doc_id: u32,
+    sentence_id: u32,
+    translation_id: Option<u32>,
+}
+
+impl Owner {
+    /// Tokens of translations have no location of their own,
+    /// they are reported at the source sentence.
+    const fn location(self, token_id: u32) -> Location {
+        match self.translation_id {
+            None => Location::token(self.doc_id, self.sentence_id, token_id),
+            Some(_) => Location::sentence(self.doc_id, self.sentence_id),
+        }
+    }
+
+    fn token(self) -> String {
+        match self.translation_id {
+            None => "Token".to_owned(),
+            Some(id) => format!("Translation {id}: token"),
+        }
+    }
+}
+
+fn error(location: Location, code: &'static str, message: String) -> Diagnostic {
+    Diagnostic::new(Severity::Error, location, code, message)
+}
+
+/// Reports duplicate IDs and IDs lower than the previous one among siblings.
+fn check_ids<I, L>(ids: I, what: &str, location: L, diagnostics: &mut Vec<Diagnostic>)
+where
+    I: IntoIterator<Item = u32>,
+    L: Fn(u32) -> Location,
+{
+    let mut seen = HashSet::new();
+    let mut prev: Option<u32> = None;
+
+    for id in ids {
+        if !seen.insert(id) {
+            diagnostics.push(error(
+                location(id),
+                "duplicate-id",
+                format!("{what} ID {id} is not unique"),
+            ));
+        } else if let Some(prev) = prev.filter(|&prev| id < prev) {
+            diagnostics.push(error(
+                location(id),
+                "unordered-id",
+                format!("{what} ID {id} follows ID {prev}"),
+            ));
+        }
+        prev = Some(id);
+    }
+}
+
+/// Returns the IDs following the greatest ID in use, all IDs if there is none.
+pub(crate) fn free_ids(max: Option<u32>) -> impl Iterator<Item = u32> {
+    let first = max.map_or(Some(0), |max| max.checked_add(1));
+    first.into_iter().flat_map(|first| first..=u32::MAX)
+}
+
+/// Takes the next ID from [`free_ids()`].
+pub(crate) fn next_free<I: Iterator<Item = u32>>(
+    free: &mut I,
+    what: &str,
+) -> Result<u32, CorporeumError> {
+    free.next()
+        .ok_or_else(|| CorporeumError::BadValue(format!("There is no free {what} ID left")))
+}
+
+/// Gives every ID not greater than the previous one the next free ID, keeping
+/// the order of the siblings. If that would exceed `u32::MAX`, all siblings
+/// are numbered from 0 instead. Returns the new IDs by their old value; only
+/// the first occurrence of a duplicate ID is mapped.
+fn renumber<'a, I: IntoIterator<Item = &'a mut u32>>(ids: I) -> HashMap<u32, u32> {
+    let mut ids: Vec<&mut u32> = ids.into_iter().collect();
+
+    let mut prev: Option<u32> = None;
+    let shifted: Option<Vec<u32>> = ids
+        .iter()
+        .map(|id| {
+            let new_id = match prev {
+                Some(prev) if **id <= prev => prev.checked_add(1)?,
+                _ => **id,
+            };
+            prev = Some(new_id);
+            Some(new_id)
+        })
+        .collect();
+    let new_ids = shifted.unwrap_or_else(|| (0..).take(ids.len()).collect());
+
+    let mut changed = HashMap::new();
+    let mut seen = HashSet::new();
+    for (id, new_id) in ids.iter_mut().zip(new_ids) {
+        let old = **id;
+        **id = new_id;
+        if seen.insert(old) && new_id != old {
+            changed.insert(old, new_id);
+        }
+    }
+
+    changed
+}
+
+fn check_tokens(tokens: &[Token], owner: Owner, diagnostics: &mut Vec<Diagnostic>) {
+    for tok in tokens.iter().filter(|tok| tok.form.is_empty()) {
+        diagnostics.push(error(
+            owner.location(tok.id),
+            "empty-form",
+            format!("{} {} has an empty form", owner.token(), tok.id),
+        ));
+    }
+    check_ids(
+        tokens.iter().map(|tok| tok.id),
+        &owner.token(),
+        |id| owner.location(id),
+        diagnostics,
+    );
+}
+
+/// Empty forms become `_`, the CoNLL-U placeholder, and heads follow renumbered tokens.
+fn repair_tokens(tokens: &mut [Token]) {
+    for tok in tokens.iter_mut().filter(|tok| tok.form.is_empty()) {
+        "_".clone_into(&mut tok.form);
+    }
+
+    let changed = renumber(tokens.iter_mut().map(|tok| &mut tok.id));
+    if !changed.is_empty() {
+        for tok in tokens {
+            tok.remap_heads(|id| Some(changed.get(&id).copied().unwrap_or(id)));
+        }
+    }
+}
+
+fn check_sentence(doc_id: u32, sent: &Sentence<Source>, diagnostics: &mut Vec<Diagnostic>) {
+    let location = Location::sentence(doc_id, sent.id);
+    for trans in &sent.translations {
+        let owner = Owner {
+            doc_id,
+            sentence_id: sent.id,
+            translation_id: Some(trans.id),
+        };
+        check_tokens(&trans.tokens, owner, diagnostics);
+        if trans.tokens.is_empty() {
+            diagnostics.push(error(
+                location,
+                "empty-translation",
+                format!("Translation {} has no tokens", trans.id),
+            ));
+        }
+    }
+    check_ids(
+        sent.translations
+            .iter()
+            .filter(|trans| !trans.tokens.is_empty())
+            .map(|trans| trans.id),
+        "Translation",
+        |_| location,
+        diagnostics,
+    );
+
+    let owner = Owner {
+        doc_id,
+        sentence_id: sent.id,
+        translation_id: None,
+    };
+    check_tokens(&sent.tokens, owner, diagnostics);
+}
+
+fn check_doc(doc: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    for sent in &doc.sentences {
+        check_sentence(doc.id, sent, diagnostics);
+        if sent.tokens.is_empty() {
+            diagnostics.push(error(
+                Location::sentence(doc.id, sent.id),
+                "empty-sentence",
+                format!("Sentence {} has no tokens", sent.id),
+            ));
+        }
+    }
+    check_ids(
+        doc.sentences
+            .iter()
+            .filter(|sent| !sent.tokens.is_empty())
+            .map(|sent| sent.id),
+        "Sentence",
+        |id| Location::sentence(doc.id, id),
+        diagnostics,
+    );
+}
+
+fn check_docs(docs: &[Document], diagnostics: &mut Vec<Diagnostic>) {
+    for doc in docs.iter().filter(|doc| doc.sentences.is_empty()) {
+        diagnostics.push(error(
+            Location::doc(doc.id),
+            "empty-document",
+            format!("Document {} has no sentences", doc.id),
+        ));
+    }
+    check_ids(
+        docs.iter()
+            .filter(|doc| !doc.sentences.is_empty())
+            .map(|doc| doc.id),
+        "Document",
+        Location::doc,
+        diagnostics,
+    );
+}
+
+impl Corpus {
+    /// Checks the structure of the corpus: document, sentence, translation and
+    /// token IDs must be unique and increasing among their siblings, documents,
+    /// sentences and translations must not be empty and token forms must not
+    /// be empty.
+    ///
+    /// Nothing enforces these rules while a corpus is edited through
+    /// [`docs_mut()`](Self::docs_mut) or when it is loaded from a file.
+    /// Every problem is an error, in corpus order.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, Tokenizer};
+    ///
+    /// let mut corp = Corpus::new();
+    /// let tokenizer = Tokenizer::new("en");
+    /// corp.import_text("Hello there. Bye now.", &tokenizer).unwrap();
+    /// assert!(corp.check_integrity().is_empty());
+    ///
+    /// // `create_doc()` hands out the same ID until the document is added
+    /// let empty = corp.create_doc().unwrap();
+    /// let mut first = corp.create_doc().unwrap();
+    /// let mut second = corp.create_doc().unwrap();
+    /// for doc in [&mut first, &mut second] {
+    ///     let mut sent = doc.create_sentence("en").unwrap();
+    ///     sent.add_token(sent.create_token("Hi").unwrap());
+    ///     doc.add_sentence(sent).unwrap();
+    /// }
+    /// corp.docs_mut().extend([empty, first, second]);
+    ///
+    /// let codes: Vec<_> = corp.check_integrity().iter().map(|d| d.code()).collect();
+    /// assert_eq!(codes, ["empty-document", "duplicate-id"]);
+    ///
+    /// assert_eq!(corp.repair().len(), 2);
+    /// assert!(corp.check_integrity().is_empty());
+    /// let ids: Vec<_> = corp.docs().iter().map(|doc| doc.doc_id()).collect();
+    /// assert_eq!(ids, [0, 1, 2]);
+    /// ```
+    pub fn check_integrity(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for doc in &self.documents {
+            check_doc(doc, &mut diagnostics);
+        }
+        check_docs(&self.documents, &mut diagnostics);
+        diagnostics
+    }
+
+    /// Fixes the problems found by [`check_integrity()`](Self::check_integrity)
+    /// and returns them.
+    ///
+    /// Empty documents, sentences and translations are removed and empty token
+    /// forms are replaced by `_`. A duplicate or decreasing ID is replaced by
+    /// the ID following the previous sibling, so the order is kept. Heads and
+    /// enhanced dependencies are updated for renumbered tokens; with duplicate
+    /// token IDs they keep pointing to the first token with that ID.
+    pub fn repair(&mut self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for doc in &mut self.documents {
+            check_doc(doc, &mut diagnostics);
+
+            for sent in &mut doc.sentences {
+                for trans in &mut sent.translations {
+                    repair_tokens(&mut trans.tokens);
+                }
+                sent.translations.retain(|trans| !trans.tokens.is_empty());
+                renumber(sent.translations.iter_mut().map(|trans| &mut trans.id));
+                repair_tokens(&mut sent.tokens);
+            }
+            doc.sentences.retain(|sent| !sent.tokens.is_empty());
+            renumber(doc.sentences.iter_mut().map(|sent| &mut sent.id));
+        }
+
+        check_docs(&self.documents, &mut diagnostics);
+        self.documents.retain(|doc| !doc.sentences.is_empty());
+        renumber(self.documents.iter_mut().map(|doc| &mut doc.id));
+
+        diagnostics
+    }
+
+    /// Loads a corpus like [`load()`](Self::load) and checks its integrity,
+    /// see [`check_integrity()`](Self::check_integrity).
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use uniform::{CorporeumError, Corpus, IntegrityMode};
+    ///
+    /// let json = r#"{"documents":[{"id":0,"sentences":[{"t":null,"id":0,"lang":"en","tokens":[
+    ///     {"id":0,"form":"Hi","head":"0"},{"id":0,"form":""},
+    ///     {"id":1,"form":"you","head":"1"},{"id":2,"form":"!","head":"2"}]}]}]}"#;
+    ///
+    /// let err = Corpus::load_with(Cursor::new(json), IntegrityMode::Reject).unwrap_err();
+    /// assert!(matches!(err, CorporeumError::Integrity(problems) if problems.len() == 2));
+    ///
+    /// let corp = Corpus::load_with(Cursor::new(json), IntegrityMode::Repair).unwrap();
+    /// let tokens = corp.doc(0).unwrap().sentence(0).unwrap().tokens();
+    /// let forms: Vec<_> = tokens.iter().map(|t| t.form()).collect();
+    /// assert_eq!(forms, ["Hi", "_", "you", "!"]);
+    /// // "!" still depends on "you", which is now token 2
+    /// assert_eq!(tokens[3].head(), Some("3"));
+    /// ```
+    ///
+    /// # Errors
+    /// This will return an error if:
+    /// - The corpus could not be loaded.
+    /// - The corpus has structural problems and `mode` is [`IntegrityMode::Reject`].
+    pub fn load_with<R: Read + Seek>(
+        source: R,
+        mode: IntegrityMode,
+    ) -> Result<Self, CorporeumError> {
+        let mut corpus = Self::load(source)?;
+
+        match mode {
+            IntegrityMode::Reject => corpus.ensure_integrity()?,
+            IntegrityMode::Repair => {
+                corpus.repair();
+            }
+        }
+
+        Ok(corpus)
+    }
+
+    /// Returns an error if [`check_integrity()`](Self::check_integrity) finds any problems.
+    ///
+    /// # Errors
+    /// This will return [`CorporeumError::Integrity`] with all problems found.
+    pub fn ensure_integrity(&self) -> Result<(), CorporeumError> {
+        let diagnostics = self.check_integrity();
+        if diagnostics.is_empty() {
+            Ok(())
+        } else {
+            Err(CorporeumError::Integrity(diagnostics))
+        }
+    }
+
+    /// Saves the corpus like [`save_into()`](Self::save_into) after making sure
+    /// that it passes [`check_integrity()`](Self::check_integrity).
+    ///
+    /// # Errors
+    /// This will return an error if:
+    /// - The corpus has structural problems, nothing is written then.
+    /// - The serialization fails
+    pub fn save_into_checked<W: Write>(&self, dest: W) -> Result<(), CorporeumError> {
+        self.ensure_integrity()?;
+        self.save_into(dest)
+    }
+}
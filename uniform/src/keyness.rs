@@ -0,0 +1,188 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    freq::{FrequencyEntry, FrequencyList},
+    schema::{Corpus, Document},
+    TokenField,
+};
+
+/// Keyness score used to rank keywords.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeynessMeasure {
+    /// Log-likelihood (G²), negative for items underused in the focus corpus.
+    LogLikelihood,
+    /// %DIFF, the difference of the relative frequencies in percent of the
+    /// reference frequency. Infinite for items missing from the reference.
+    PercentDiff,
+    /// Simple maths, `(fpm_focus + n) / (fpm_reference + n)` with relative
+    /// frequencies per million and the smoothing parameter `n` (usually `1`).
+    SimpleMaths(f64),
+}
+
+/// A row of a keyness table, see [`FrequencyList::keywords()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyword {
+    values: Vec<String>,
+    focus_freq: u64,
+    reference_freq: u64,
+    focus_total: u64,
+    reference_total: u64,
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn per_million(freq: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        freq as f64 * 1_000_000.0 / total as f64
+    }
+}
+
+impl Keyword {
+    /// Returns the values of the compared fields.
+    pub fn values(&self) -> &[String] {
+        &self.values
+    }
+
+    pub const fn focus_freq(&self) -> u64 {
+        self.focus_freq
+    }
+
+    pub const fn reference_freq(&self) -> u64 {
+        self.reference_freq
+    }
+
+    /// Returns the relative frequency per million tokens in the focus corpus.
+    pub fn focus_per_million(&self) -> f64 {
+        per_million(self.focus_freq, self.focus_total)
+    }
+
+    /// Returns the relative frequency per million tokens in the reference corpus.
+    pub fn reference_per_million(&self) -> f64 {
+        per_million(self.reference_freq, self.reference_total)
+    }
+
+    /// Returns the score of the given measure.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn score(&self, measure: KeynessMeasure) -> f64 {
+        let (focus, reference) = (self.focus_per_million(), self.reference_per_million());
+        match measure {
+            KeynessMeasure::LogLikelihood => {
+                let (a, b) = (self.focus_freq as f64, self.reference_freq as f64);
+                let (c, d) = (self.focus_total as f64, self.reference_total as f64);
+                let e1 = c * (a + b) / (c + d);
+                let e2 = d * (a + b) / (c + d);
+                let term = |o: f64, e: f64| if o > 0.0 { o * (o / e).ln() } else { 0.0 };
+                let ll = 2.0 * (term(a, e1) + term(b, e2));
+                if focus < reference {
+                    -ll
+                } else {
+                    ll
+                }
+            }
+            KeynessMeasure::PercentDiff => {
+                if reference == 0.0 {
+                    f64::INFINITY
+                } else {
+                    (focus - reference) * 100.0 / reference
+                }
+            }
+            KeynessMeasure::SimpleMaths(n) => (focus + n) / (reference + n),
+        }
+    }
+}
+
+impl FrequencyList {
+    /// Compares this (focus) list with a reference list and ranks the items
+    /// by a keyness measure, highest score first.
+    ///
+    /// Both lists should count the same fields. Items occurring fewer than
+    /// `min_freq` times in the focus list are left out; with `min_freq` `0`
+    /// items occurring only in the reference are included as well.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, KeynessMeasure, TokenField, Tokenizer};
+    ///
+    /// let tokenizer = Tokenizer::new("en");
+    /// let mut focus = Corpus::new();
+    /// focus.import_text("The goal was a great goal. The team won.", &tokenizer).unwrap();
+    /// let mut reference = Corpus::new();
+    /// reference.import_text("The cat sat. The dog ran. The team met.", &tokenizer).unwrap();
+    ///
+    /// let keywords = focus
+    ///     .frequency_list(&[TokenField::Form])
+    ///     .keywords(&reference.frequency_list(&[TokenField::Form]), KeynessMeasure::LogLikelihood, 2);
+    /// assert_eq!(keywords[0].values(), ["goal"]);
+    /// assert_eq!(keywords.len(), 3);
+    /// ```
+    pub fn keywords(
+        &self,
+        reference: &Self,
+        measure: KeynessMeasure,
+        min_freq: u64,
+    ) -> Vec<Keyword> {
+        let candidates: BTreeSet<&[String]> = self
+            .entries()
+            .iter()
+            .filter(|e| e.freq() >= min_freq)
+            .map(FrequencyEntry::values)
+            .chain(
+                reference
+                    .entries()
+                    .iter()
+                    .filter(|_| min_freq == 0)
+                    .map(FrequencyEntry::values),
+            )
+            .collect();
+
+        let mut keywords: Vec<Keyword> = candidates
+            .into_iter()
+            .map(|values| {
+                let key: Vec<&str> = values.iter().map(String::as_str).collect();
+                Keyword {
+                    values: values.to_vec(),
+                    focus_freq: self.freq(&key),
+                    reference_freq: reference.freq(&key),
+                    focus_total: self.total_tokens(),
+                    reference_total: reference.total_tokens(),
+                }
+            })
+            .collect();
+
+        keywords.sort_by(|a, b| b.score(measure).total_cmp(&a.score(measure)));
+        keywords
+    }
+}
+
+impl Corpus {
+    /// Extracts keywords of this corpus compared to a reference corpus,
+    /// see [`FrequencyList::keywords()`].
+    pub fn keywords(
+        &self,
+        reference: &Self,
+        field: TokenField,
+        measure: KeynessMeasure,
+        min_freq: u64,
+    ) -> Vec<Keyword> {
+        self.frequency_list(&[field]).keywords(
+            &reference.frequency_list(&[field]),
+            measure,
+            min_freq,
+        )
+    }
+
+    /// Extracts keywords of the documents accepted by `filter` compared to the
+    /// rest of this corpus, see [`FrequencyList::keywords()`].
+    pub fn subcorpus_keywords<F: Fn(&Document) -> bool>(
+        &self,
+        filter: F,
+        field: TokenField,
+        measure: KeynessMeasure,
+        min_freq: u64,
+    ) -> Vec<Keyword> {
+        let focus = self.frequency_list_by(&[field], &filter);
+        let reference = self.frequency_list_by(&[field], |doc| !filter(doc));
+        focus.keywords(&reference, measure, min_freq)
+    }
+}
@@ -2,14 +2,64 @@
 //
 //! A library for working with text corpora.
 //
+pub use agreement::{Agreement, AttachmentAgreement, ConfusionMatrix, SpanAgreement, SpanScore};
+#[cfg(feature = "parquet")]
+pub use arrow_array::RecordBatch;
+pub use collocation::{AssociationMeasure, Collocate, CollocationWindow};
+pub use concordance::{Concordance, ContextWidth, KwicLine, SortKey};
+pub use detokenizer::Detokenizer;
+pub use diff::{Change, CorpusDiff, Property};
+pub use eaf::{EafOptions, EAF_TIER};
 pub use error::CorporeumError;
-pub use schema::{Author, Corpus, Document, Metadata, Sentence, Token};
+pub use eval::{EvalMetric, EvalScore, Evaluation};
+pub use freq::{FrequencyEntry, FrequencyList};
+pub use history::{History, Transaction};
+pub use igt::{IgtFormat, MORPHEME_SEGMENTATION};
+pub use index::{CorpusIndex, Position};
+pub use integrity::IntegrityMode;
+pub use keyness::{KeynessMeasure, Keyword};
+pub use patch::{Conflict, Merge};
+pub use query::{Match, Query};
+pub use schema::{Author, Corpus, Document, Metadata, Sentence, Speaker, Token};
+pub use stats::{CorpusStats, LengthStats};
+pub use subtitle::{Segmentation, SubtitleFormat, CUE_ID, CUE_SETTINGS};
+pub use text::{TextToken, Tokenizer};
+pub use token::TokenField;
+pub use tree::{TreeMatch, TreePattern};
+pub use validate::{Diagnostic, Location, Severity, SENTENCE_TEXT};
+pub use xliff::{XliffVersion, XLIFF_NOTE, XLIFF_SEGMENT_ID, XLIFF_UNIT_ID};
 
+mod agreement;
 mod author;
+mod collocation;
+#[cfg(feature = "parquet")]
+mod columnar;
+mod concordance;
 mod corpus;
+mod detokenizer;
+mod diff;
 mod document;
+mod eaf;
+mod edit;
 mod error;
+mod eval;
+mod freq;
+mod history;
+mod igt;
+mod index;
+mod integrity;
+mod keyness;
 mod metadata;
+mod patch;
+mod query;
 mod schema;
 mod sentence;
+mod speaker;
+mod stats;
+mod subtitle;
+mod text;
 mod token;
+mod tree;
+mod validate;
+mod xliff;
+mod xml;
@@ -1,5 +1,6 @@
 use crate::schema::Author;
 use crate::schema::Metadata;
+use crate::schema::Speaker;
 
 impl Metadata {
     pub(crate) fn new(name: &str) -> Self {
@@ -9,6 +10,7 @@ impl Metadata {
             description: None,
             modified: None,
             name: name.to_owned(),
+            speakers: Vec::new(),
             version: 1,
         }
     }
@@ -44,7 +46,7 @@ impl Metadata {
     ///
     /// # Example
     /// ```no_run
-    /// use corporum::Metadata;
+    /// use uniform::Metadata;
     ///
     /// let meta: Metadata = todo!();
     ///
@@ -68,8 +70,8 @@ impl Metadata {
         self.authors
             .iter()
             .filter(|author| {
-                (first_name.map_or(false, |name| name == author.first_name))
-                    | (last_name.map_or(false, |name| name == author.last_name))
+                (first_name.is_some_and(|name| name == author.first_name))
+                    | (last_name.is_some_and(|name| name == author.last_name))
             })
             .collect()
     }
@@ -78,10 +80,10 @@ impl Metadata {
     ///
     /// # Example
     /// ```no_run
-    /// use corporum::Metadata;
+    /// use uniform::Metadata;
     ///
     /// let meta: Metadata = todo!();
-    /// let author = meta.get_author("John", "Kenny").unwrap();
+    /// let author = meta.author("John", "Kenny").unwrap();
     ///
     /// println!("{author:?}");
     /// ```
@@ -91,6 +93,54 @@ impl Metadata {
             .find(|author| author.first_name == first_name && author.last_name == last_name)
     }
 
+    /// Returns a reference to the list of speakers.
+    pub const fn speakers(&self) -> &Vec<Speaker> {
+        &self.speakers
+    }
+
+    /// Adds a new speaker with the specified ID and returns a mutable reference to it.
+    /// If a speaker with this ID already exists, it is returned instead.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::Corpus;
+    ///
+    /// let mut corp = Corpus::new();
+    /// corp.add_metadata("Dialogues");
+    ///
+    /// let meta = corp.metadata_mut().unwrap();
+    /// let speaker = meta.add_speaker("SPK1");
+    /// speaker.set_age(34);
+    /// speaker.set_dialect("Záhorie");
+    ///
+    /// assert_eq!(meta.speaker("SPK1").unwrap().age(), Some(34));
+    /// ```
+    pub fn add_speaker(&mut self, id: &str) -> &mut Speaker {
+        let pos = match self.speakers.iter().position(|s| s.id == id) {
+            Some(pos) => pos,
+            None => {
+                self.speakers.push(Speaker::new(id));
+                self.speakers.len() - 1
+            }
+        };
+        &mut self.speakers[pos]
+    }
+
+    /// Looks for a speaker with the specified ID and returns a reference to it if exists.
+    pub fn speaker(&self, id: &str) -> Option<&Speaker> {
+        self.speakers.iter().find(|s| s.id == id)
+    }
+
+    /// Looks for a speaker with the specified ID and returns a mutable reference to it if exists.
+    pub fn speaker_mut(&mut self, id: &str) -> Option<&mut Speaker> {
+        self.speakers.iter_mut().find(|s| s.id == id)
+    }
+
+    /// Removes the speaker with the specified ID.
+    pub fn remove_speaker(&mut self, id: &str) {
+        self.speakers.retain(|s| s.id != id);
+    }
+
     /// Returns the description of this metadata.
     #[allow(clippy::missing_panics_doc)]
     pub fn description(&self) -> &str {
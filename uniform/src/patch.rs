@@ -0,0 +1,769 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::ops::Bound;
+
+use crate::{
+    diff::{Change, CorpusDiff, Property},
+    schema::{Corpus, Document, Sentence, Source},
+    validate::Location,
+    CorporeumError, TokenField,
+};
+
+fn not_found(location: Location) -> CorporeumError {
+    CorporeumError::ElementNotFound(format!("{location} does not exist"))
+}
+
+fn conflict(location: Location, message: &str) -> CorporeumError {
+    CorporeumError::BadValue(format!("{location}: {message}"))
+}
+
+/// Inserts an element before the first one with a greater ID.
+fn insert_by_id<E, F: Fn(&E) -> u32>(elements: &mut Vec<E>, element: E, id: F) {
+    let pos = elements
+        .iter()
+        .position(|e| id(e) > id(&element))
+        .unwrap_or(elements.len());
+    elements.insert(pos, element);
+}
+
+fn set_meta(
+    meta: &mut BTreeMap<String, String>,
+    location: Location,
+    key: &str,
+    old: Option<&str>,
+    new: Option<&str>,
+) -> Result<(), CorporeumError> {
+    if meta.get(key).map(String::as_str) != old {
+        return Err(conflict(
+            location,
+            &format!("metadata entry '{key}' does not have the expected value"),
+        ));
+    }
+
+    match new {
+        Some(value) => meta.insert(key.to_owned(), value.to_owned()),
+        None => meta.remove(key),
+    };
+    Ok(())
+}
+
+fn doc_mut(corpus: &mut Corpus, doc_id: u32) -> Result<&mut Document, CorporeumError> {
+    corpus
+        .doc_mut(doc_id)
+        .ok_or_else(|| not_found(Location::doc(doc_id)))
+}
+
+fn sentence_mut(
+    corpus: &mut Corpus,
+    doc_id: u32,
+    sentence_id: u32,
+) -> Result<&mut Sentence<Source>, CorporeumError> {
+    doc_mut(corpus, doc_id)?
+        .sentence_mut(sentence_id)
+        .ok_or_else(|| not_found(Location::sentence(doc_id, sentence_id)))
+}
+
+fn set_value(
+    value: &mut Option<String>,
+    location: Location,
+    property: Property,
+    old: Option<&str>,
+    new: Option<&str>,
+) -> Result<(), CorporeumError> {
+    if value.as_deref() != old {
+        return Err(conflict(
+            location,
+            &format!("{property} does not have the expected value"),
+        ));
+    }
+    *value = new.map(str::to_owned);
+    Ok(())
+}
+
+fn set_timing(
+    start: &mut Option<u64>,
+    end: &mut Option<u64>,
+    location: Location,
+    old: (Option<u64>, Option<u64>),
+    new: (Option<u64>, Option<u64>),
+) -> Result<(), CorporeumError> {
+    if (*start, *end) != old {
+        return Err(conflict(
+            location,
+            "timing does not have the expected value",
+        ));
+    }
+    (*start, *end) = new;
+    Ok(())
+}
+
+impl Change {
+    /// Applies this change to a corpus, checking that the corpus is in the
+    /// state the change was made from.
+    pub(crate) fn apply_to(&self, corpus: &mut Corpus) -> Result<(), CorporeumError> {
+        let location = self.location();
+        let exists = || conflict(location, "already exists");
+
+        match self {
+            Self::CorpusMetadata { old, new } => {
+                if corpus.metadata != *old {
+                    return Err(conflict(
+                        location,
+                        "metadata does not have the expected value",
+                    ));
+                }
+                corpus.metadata.clone_from(new);
+            }
+            Self::DocumentAdded { document } => {
+                if corpus.doc(document.id).is_some() {
+                    return Err(exists());
+                }
+                insert_by_id(&mut corpus.documents, document.clone(), |d| d.id);
+            }
+            Self::DocumentRemoved { document } => corpus.remove_doc(document.id)?,
+            Self::DocumentMeta {
+                doc_id,
+                key,
+                old,
+                new,
+            } => set_meta(
+                &mut doc_mut(corpus, *doc_id)?.meta,
+                location,
+                key,
+                old.as_deref(),
+                new.as_deref(),
+            )?,
+            Self::DocumentChanged {
+                doc_id,
+                property,
+                old,
+                new,
+            } => {
+                let doc = doc_mut(corpus, *doc_id)?;
+                let value = match property {
+                    Property::Description => &mut doc.description,
+                    Property::Source => &mut doc.source,
+                    Property::Lang | Property::Speaker => {
+                        return Err(conflict(location, &format!("documents have no {property}")))
+                    }
+                };
+                set_value(value, location, *property, old.as_deref(), new.as_deref())?;
+            }
+            Self::SentenceAdded { doc_id, sentence } => {
+                let doc = doc_mut(corpus, *doc_id)?;
+                if doc.sentence(sentence.id).is_some() {
+                    return Err(exists());
+                }
+                insert_by_id(&mut doc.sentences, sentence.clone(), |s| s.id);
+            }
+            Self::SentenceRemoved { doc_id, sentence } => {
+                doc_mut(corpus, *doc_id)?.remove_sentence(sentence.id)?;
+            }
+            Self::SentenceMeta {
+                doc_id,
+                sentence_id,
+                key,
+                old,
+                new,
+            } => set_meta(
+                &mut sentence_mut(corpus, *doc_id, *sentence_id)?.meta,
+                location,
+                key,
+                old.as_deref(),
+                new.as_deref(),
+            )?,
+            Self::SentenceChanged {
+                doc_id,
+                sentence_id,
+                property,
+                old,
+                new,
+            } => {
+                let sent = sentence_mut(corpus, *doc_id, *sentence_id)?;
+                match property {
+                    Property::Lang => {
+                        let Some(lang) = new else {
+                            return Err(conflict(location, "the language cannot be removed"));
+                        };
+                        if old.as_deref() != Some(sent.lang.as_str()) {
+                            return Err(conflict(
+                                location,
+                                "lang does not have the expected value",
+                            ));
+                        }
+                        sent.lang.clone_from(lang);
+                    }
+                    Property::Speaker => set_value(
+                        &mut sent.speaker,
+                        location,
+                        *property,
+                        old.as_deref(),
+                        new.as_deref(),
+                    )?,
+                    Property::Description | Property::Source => {
+                        return Err(conflict(location, &format!("sentences have no {property}")))
+                    }
+                }
+            }
+            Self::SentenceTiming {
+                doc_id,
+                sentence_id,
+                old,
+                new,
+            } => {
+                let sent = sentence_mut(corpus, *doc_id, *sentence_id)?;
+                set_timing(&mut sent.start, &mut sent.end, location, *old, *new)?;
+            }
+            Self::TranslationAdded {
+                doc_id,
+                sentence_id,
+                translation,
+            } => {
+                let sent = sentence_mut(corpus, *doc_id, *sentence_id)?;
+                if sent.translation(translation.id).is_some() {
+                    return Err(conflict(
+                        location,
+                        &format!("translation {} already exists", translation.id),
+                    ));
+                }
+                insert_by_id(&mut sent.translations, translation.clone(), |t| t.id);
+            }
+            Self::TranslationRemoved {
+                doc_id,
+                sentence_id,
+                translation,
+            } => {
+                sentence_mut(corpus, *doc_id, *sentence_id)?.remove_translation(translation.id)?;
+            }
+            Self::TokenAdded {
+                doc_id,
+                sentence_id,
+                token,
+            } => {
+                let sent = sentence_mut(corpus, *doc_id, *sentence_id)?;
+                if sent.token(token.id).is_some() {
+                    return Err(exists());
+                }
+                insert_by_id(&mut sent.tokens, token.clone(), |t| t.id);
+            }
+            Self::TokenRemoved {
+                doc_id,
+                sentence_id,
+                token,
+            } => {
+                let sent = sentence_mut(corpus, *doc_id, *sentence_id)?;
+                let pos = sent
+                    .tokens
+                    .iter()
+                    .position(|t| t.id == token.id)
+                    .ok_or_else(|| not_found(location))?;
+                sent.tokens.remove(pos);
+            }
+            Self::TokensRenumbered {
+                doc_id,
+                sentence_id,
+                ids,
+            } => {
+                let sent = sentence_mut(corpus, *doc_id, *sentence_id)?;
+                if let Some((id, _)) = ids.iter().find(|&&(id, _)| sent.token(id).is_none()) {
+                    return Err(not_found(Location::token(*doc_id, *sentence_id, *id)));
+                }
+
+                let ids: HashMap<u32, u32> = ids.iter().copied().collect();
+                for tok in &mut sent.tokens {
+                    tok.id = ids.get(&tok.id).copied().unwrap_or(tok.id);
+                    tok.remap_heads(|id| Some(ids.get(&id).copied().unwrap_or(id)));
+                }
+            }
+            Self::TokenChanged {
+                doc_id,
+                sentence_id,
+                token_id,
+                field,
+                old,
+                new,
+            } => {
+                let tok = sentence_mut(corpus, *doc_id, *sentence_id)?
+                    .token_mut(*token_id)
+                    .ok_or_else(|| not_found(location))?;
+                if tok.get(*field) != old.as_deref() {
+                    return Err(conflict(
+                        location,
+                        &format!("{field} does not have the expected value"),
+                    ));
+                }
+                match new {
+                    Some(value) => tok.set(*field, value),
+                    None => tok.remove(*field),
+                }
+            }
+            Self::TokenTiming {
+                doc_id,
+                sentence_id,
+                token_id,
+                old,
+                new,
+            } => {
+                let tok = sentence_mut(corpus, *doc_id, *sentence_id)?
+                    .token_mut(*token_id)
+                    .ok_or_else(|| not_found(location))?;
+                set_timing(&mut tok.start, &mut tok.end, location, *old, *new)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A step of the path from the corpus to the element or value a change touches.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum Step<'a> {
+    Metadata,
+    Doc(u32),
+    Sentence(u32),
+    Translation(u32),
+    Renumber,
+    Token(u32),
+    Meta(&'a str),
+    Property(Property),
+    Timing,
+    Field(TokenField),
+}
+
+fn path(change: &Change) -> Vec<Step<'_>> {
+    match change {
+        Change::CorpusMetadata { .. } => vec![Step::Metadata],
+        Change::DocumentAdded { document } | Change::DocumentRemoved { document } => {
+            vec![Step::Doc(document.id)]
+        }
+        Change::DocumentMeta { doc_id, key, .. } => vec![Step::Doc(*doc_id), Step::Meta(key)],
+        Change::DocumentChanged {
+            doc_id, property, ..
+        } => vec![Step::Doc(*doc_id), Step::Property(*property)],
+        Change::SentenceAdded { doc_id, sentence }
+        | Change::SentenceRemoved { doc_id, sentence } => {
+            vec![Step::Doc(*doc_id), Step::Sentence(sentence.id)]
+        }
+        Change::SentenceMeta {
+            doc_id,
+            sentence_id,
+            key,
+            ..
+        } => vec![
+            Step::Doc(*doc_id),
+            Step::Sentence(*sentence_id),
+            Step::Meta(key),
+        ],
+        Change::SentenceChanged {
+            doc_id,
+            sentence_id,
+            property,
+            ..
+        } => vec![
+            Step::Doc(*doc_id),
+            Step::Sentence(*sentence_id),
+            Step::Property(*property),
+        ],
+        Change::SentenceTiming {
+            doc_id,
+            sentence_id,
+            ..
+        } => vec![
+            Step::Doc(*doc_id),
+            Step::Sentence(*sentence_id),
+            Step::Timing,
+        ],
+        Change::TranslationAdded {
+            doc_id,
+            sentence_id,
+            translation,
+        }
+        | Change::TranslationRemoved {
+            doc_id,
+            sentence_id,
+            translation,
+        } => vec![
+            Step::Doc(*doc_id),
+            Step::Sentence(*sentence_id),
+            Step::Translation(translation.id),
+        ],
+        Change::TokensRenumbered {
+            doc_id,
+            sentence_id,
+            ..
+        } => vec![
+            Step::Doc(*doc_id),
+            Step::Sentence(*sentence_id),
+            Step::Renumber,
+        ],
+        Change::TokenAdded {
+            doc_id,
+            sentence_id,
+            token,
+        }
+        | Change::TokenRemoved {
+            doc_id,
+            sentence_id,
+            token,
+        } => vec![
+            Step::Doc(*doc_id),
+            Step::Sentence(*sentence_id),
+            Step::Token(token.id),
+        ],
+        Change::TokenChanged {
+            doc_id,
+            sentence_id,
+            token_id,
+            field,
+            ..
+        } => vec![
+            Step::Doc(*doc_id),
+            Step::Sentence(*sentence_id),
+            Step::Token(*token_id),
+            Step::Field(*field),
+        ],
+        Change::TokenTiming {
+            doc_id,
+            sentence_id,
+            token_id,
+            ..
+        } => vec![
+            Step::Doc(*doc_id),
+            Step::Sentence(*sentence_id),
+            Step::Token(*token_id),
+            Step::Timing,
+        ],
+    }
+}
+
+/// Returns the sentence of a change touching its tokens.
+const fn token_sentence(change: &Change) -> Option<(u32, u32)> {
+    match change {
+        Change::TokenAdded {
+            doc_id,
+            sentence_id,
+            ..
+        }
+        | Change::TokenRemoved {
+            doc_id,
+            sentence_id,
+            ..
+        }
+        | Change::TokensRenumbered {
+            doc_id,
+            sentence_id,
+            ..
+        }
+        | Change::TokenChanged {
+            doc_id,
+            sentence_id,
+            ..
+        }
+        | Change::TokenTiming {
+            doc_id,
+            sentence_id,
+            ..
+        } => Some((*doc_id, *sentence_id)),
+        _ => None,
+    }
+}
+
+/// Changes of tokens of one side of a merge, by sentence.
+#[derive(Default)]
+struct TokenChanges<'a> {
+    all: Vec<usize>,
+    /// Added, removed and renumbered tokens, which change the meaning of IDs.
+    structure: Vec<&'a Change>,
+}
+
+fn token_changes(changes: &[Change]) -> HashMap<(u32, u32), TokenChanges<'_>> {
+    let mut sentences: HashMap<(u32, u32), TokenChanges<'_>> = HashMap::new();
+    for (i, change) in changes.iter().enumerate() {
+        let Some(sentence) = token_sentence(change) else {
+            continue;
+        };
+        let entry = sentences.entry(sentence).or_default();
+        entry.all.push(i);
+        if matches!(
+            change,
+            Change::TokenAdded { .. }
+                | Change::TokenRemoved { .. }
+                | Change::TokensRenumbered { .. }
+        ) {
+            entry.structure.push(change);
+        }
+    }
+    sentences
+}
+
+/// Changes of both sides of a merge that touch the same element, see [`Corpus::merge()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    location: Location,
+    ours: Vec<Change>,
+    theirs: Vec<Change>,
+}
+
+impl Conflict {
+    /// Returns the document, sentence or token in conflict.
+    pub const fn location(&self) -> Location {
+        self.location
+    }
+
+    /// Returns the changes of our side, which are kept in the merged corpus.
+    pub fn ours(&self) -> &[Change] {
+        &self.ours
+    }
+
+    /// Returns the changes of their side, which are left out of the merged corpus.
+    pub fn theirs(&self) -> &[Change] {
+        &self.theirs
+    }
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "conflict at {}", self.location)?;
+        for change in &self.ours {
+            writeln!(f, "  ours:   {change}")?;
+        }
+        for change in &self.theirs {
+            writeln!(f, "  theirs: {change}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of a three-way merge, see [`Corpus::merge()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Merge {
+    corpus: Corpus,
+    conflicts: Vec<Conflict>,
+}
+
+impl Merge {
+    /// Returns the merged corpus.
+    pub const fn corpus(&self) -> &Corpus {
+        &self.corpus
+    }
+
+    pub fn into_corpus(self) -> Corpus {
+        self.corpus
+    }
+
+    /// Returns the conflicts in the order of their changes.
+    pub fn conflicts(&self) -> &[Conflict] {
+        &self.conflicts
+    }
+
+    /// Returns `true` if the merge has no conflicts.
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+impl Corpus {
+    /// Applies a patch created with [`diff()`](Self::diff) to this corpus.
+    ///
+    /// Every change must fit the corpus: removed and changed elements must
+    /// exist, added ones must not, and changed values must still have their
+    /// old value. Added elements are inserted before the first sibling with a
+    /// greater ID. The patch is applied completely or not at all.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, CorpusDiff, TokenField, Tokenizer};
+    ///
+    /// let mut v1 = Corpus::new();
+    /// v1.import_text("Dogs bark.", &Tokenizer::new("en")).unwrap();
+    /// let mut v2 = v1.clone();
+    /// let sent = v2.doc_mut(0).unwrap().sentence_mut(0).unwrap();
+    /// sent.token_mut(0).unwrap().set(TokenField::Lemma, "dog");
+    ///
+    /// let patch = CorpusDiff::from_json(&v1.diff(&v2).to_json().unwrap()).unwrap();
+    /// let mut copy = v1.clone();
+    /// copy.apply(&patch).unwrap();
+    /// assert_eq!(copy, v2);
+    ///
+    /// // the lemma is already set, the patch does not fit anymore
+    /// assert!(copy.apply(&patch).is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// This will return an error if a change does not fit the corpus, which
+    /// is then left unchanged.
+    pub fn apply(&mut self, patch: &CorpusDiff) -> Result<(), CorporeumError> {
+        let mut patched = self.clone();
+        for change in patch.changes() {
+            change.apply_to(&mut patched)?;
+        }
+
+        *self = patched;
+        Ok(())
+    }
+
+    /// Applies a patch without copying the corpus first. If a change does not
+    /// fit, the changes applied so far are undone, which cannot fail as every
+    /// change is exactly reversible.
+    pub(crate) fn apply_in_place(&mut self, patch: &CorpusDiff) -> Result<(), CorporeumError> {
+        for (i, change) in patch.changes().iter().enumerate() {
+            if let Err(err) = change.apply_to(self) {
+                for applied in patch.changes()[..i].iter().rev() {
+                    applied.reverse().apply_to(self)?;
+                }
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Merges two edited copies of this corpus (the common base).
+    ///
+    /// The changes of both sides are computed with [`diff()`](Self::diff) and
+    /// their changes are applied to our side. Changes touching different tokens,
+    /// different fields of a token or different metadata entries are merged
+    /// automatically, as are identical changes made on both sides. Changes
+    /// touching the same value, or an element the other side added or removed,
+    /// are conflicts: the merged corpus keeps our version and the conflict is
+    /// reported with the changes of both sides.
+    ///
+    /// Adding or removing tokens renumbers the following ones, so if the sides
+    /// did not add and remove the same tokens of a sentence, all token changes
+    /// of both sides in that sentence are a single conflict of the sentence.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, TokenField, Tokenizer};
+    ///
+    /// let mut base = Corpus::new();
+    /// base.import_text("Dogs bark.", &Tokenizer::new("en")).unwrap();
+    ///
+    /// let mut ours = base.clone();
+    /// let mut theirs = base.clone();
+    /// let set = |corp: &mut Corpus, tok, field, value| {
+    ///     let sent = corp.doc_mut(0).unwrap().sentence_mut(0).unwrap();
+    ///     sent.token_mut(tok).unwrap().set(field, value);
+    /// };
+    /// set(&mut ours, 0, TokenField::Upos, "NOUN");
+    /// set(&mut ours, 1, TokenField::Upos, "VERB");
+    /// set(&mut theirs, 0, TokenField::Lemma, "dog");
+    /// set(&mut theirs, 1, TokenField::Upos, "NOUN");
+    ///
+    /// let merge = base.merge(&ours, &theirs).unwrap();
+    /// let tokens = merge.corpus().doc(0).unwrap().sentence(0).unwrap().tokens();
+    /// assert_eq!((tokens[0].upos(), tokens[0].lemma()), (Some("NOUN"), Some("dog")));
+    /// assert_eq!(tokens[1].upos(), Some("VERB"));
+    ///
+    /// assert_eq!(merge.conflicts().len(), 1);
+    /// assert_eq!(merge.conflicts()[0].location().token_id(), Some(1));
+    ///
+    /// // inserting a token renumbers "bark", their lemma is not moved to "big"
+    /// let mut base = Corpus::new();
+    /// base.import_text("The big dog barks.", &Tokenizer::new("en")).unwrap();
+    /// let mut ours = base.clone();
+    /// let sent = ours.doc_mut(0).unwrap().sentence_mut(0).unwrap();
+    /// sent.insert_token(1, sent.create_token("very").unwrap()).unwrap();
+    /// let mut theirs = base.clone();
+    /// set(&mut theirs, 3, TokenField::Lemma, "bark");
+    ///
+    /// let merge = base.merge(&ours, &theirs).unwrap();
+    /// assert_eq!(merge.conflicts().len(), 1);
+    /// assert_eq!(merge.conflicts()[0].location().token_id(), None);
+    /// let sent = merge.corpus().doc(0).unwrap().sentence(0).unwrap();
+    /// assert_eq!(sent.to_text(), "The very big dog barks.");
+    /// assert!(sent.tokens().iter().all(|tok| tok.lemma().is_none()));
+    ///
+    /// // their timing follows "barks" after they removed "The"
+    /// let mut ours = base.clone();
+    /// ours.doc_mut(0).unwrap().set_meta("genre", "fiction");
+    /// let mut theirs = base.clone();
+    /// let sent = theirs.doc_mut(0).unwrap().sentence_mut(0).unwrap();
+    /// sent.remove_token(0).unwrap();
+    /// sent.token_mut(2).unwrap().set_timing(500, 900);
+    ///
+    /// let merge = base.merge(&ours, &theirs).unwrap();
+    /// assert!(merge.is_clean());
+    /// let sent = merge.corpus().doc(0).unwrap().sentence(0).unwrap();
+    /// assert_eq!(sent.to_text(), "big dog barks.");
+    /// assert_eq!(sent.tokens()[2].start(), Some(500));
+    /// assert_eq!(merge.corpus().doc(0).unwrap().meta("genre"), Some("fiction"));
+    /// ```
+    ///
+    /// # Errors
+    /// This will return an error if a change of their side that does not
+    /// conflict cannot be applied, e.g. because the sides use IDs inconsistently.
+    pub fn merge(&self, ours: &Self, theirs: &Self) -> Result<Merge, CorporeumError> {
+        let our_changes = self.diff(ours);
+        let their_changes = self.diff(theirs);
+        let our_changes = our_changes.changes();
+
+        let mut index: BTreeMap<Vec<Step<'_>>, Vec<usize>> = BTreeMap::new();
+        for (i, change) in our_changes.iter().enumerate() {
+            index.entry(path(change)).or_default().push(i);
+        }
+        let our_tokens = token_changes(our_changes);
+        let their_tokens = token_changes(their_changes.changes());
+
+        let mut corpus = ours.clone();
+        let mut conflicts: Vec<Conflict> = Vec::new();
+        // conflicts by location, with the indices of their changes on our side
+        let mut found: HashMap<Location, (usize, HashSet<usize>)> = HashMap::new();
+
+        for change in their_changes.changes() {
+            let path = path(change);
+            let mut clashing: Vec<usize> = Vec::new();
+            let mut location = change.location();
+
+            let renumbered = token_sentence(change).and_then(|sentence| {
+                let ours = our_tokens.get(&sentence)?;
+                (ours.structure != their_tokens[&sentence].structure).then_some((sentence, ours))
+            });
+            if let Some(((doc_id, sentence_id), ours)) = renumbered {
+                location = Location::sentence(doc_id, sentence_id);
+                clashing.extend(&ours.all);
+            } else {
+                if index
+                    .get(&path)
+                    .is_some_and(|same| same.iter().any(|&i| our_changes[i] == *change))
+                {
+                    continue;
+                }
+
+                // our changes of the same value, of an element containing it
+                // or of elements it contains
+                for len in 0..path.len() {
+                    clashing.extend(index.get(&path[..len]).into_iter().flatten());
+                }
+                clashing.extend(
+                    index
+                        .range::<[Step<'_>], _>((Bound::Included(&path[..]), Bound::Unbounded))
+                        .take_while(|(other, _)| other.starts_with(&path))
+                        .flat_map(|(_, changes)| changes),
+                );
+            }
+
+            if clashing.is_empty() {
+                change.apply_to(&mut corpus)?;
+                continue;
+            }
+
+            let (pos, seen) = found.entry(location).or_insert_with(|| {
+                conflicts.push(Conflict {
+                    location,
+                    ours: Vec::new(),
+                    theirs: Vec::new(),
+                });
+                (conflicts.len() - 1, HashSet::new())
+            });
+            let conflict = &mut conflicts[*pos];
+            conflict.theirs.push(change.clone());
+            for i in clashing {
+                if seen.insert(i) {
+                    conflict.ours.push(our_changes[i].clone());
+                }
+            }
+        }
+
+        Ok(Merge { corpus, conflicts })
+    }
+}
@@ -0,0 +1,564 @@
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::str::FromStr;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::{
+    schema::{Corpus, Sentence, Token},
+    CorporeumError, TokenField,
+};
+
+/// A compiled corpus query in a CQL-like syntax.
+///
+/// A query is a sequence of token patterns, each optionally followed by a
+/// repetition operator:
+///
+/// - `[lemma="go"]` matches a token whose lemma matches the regular expression `go`.
+///   Values are matched against the whole attribute, missing attributes count as
+///   empty strings. Any [`TokenField`] name can be used, `word` is an alias of `form`.
+/// - `[upos="NOUN" & !lemma="be|have"]`, `[upos="ADJ" | upos="DET"]` combine
+///   conditions, `!=` negates a single condition, `"%c"` after a value makes it
+///   case-insensitive (`[lemma="go"%c]`).
+/// - `[]` matches any token and `"dog"` is a shorthand for `[form="dog"]`.
+/// - `?`, `*`, `+`, `{n}`, `{n,}` and `{n,m}` repeat the preceding pattern,
+///   `(...)` groups patterns and `|` separates alternatives within a group.
+///
+/// Matches never cross sentence boundaries. A trailing `within <s .../>` clause
+/// restricts the search to sentences whose `lang`, `speaker` or metadata
+/// entries match the given regular expressions.
+///
+/// # Example
+/// ```
+/// use uniform::{Corpus, Query};
+///
+/// let mut corp = Corpus::new();
+/// let mut doc = corp.create_doc().unwrap();
+/// let mut sent = doc.create_sentence("en").unwrap();
+/// for (form, lemma, upos) in [
+///     ("They", "they", "PRON"),
+///     ("went", "go", "VERB"),
+///     ("to", "to", "ADP"),
+///     ("school", "school", "NOUN"),
+/// ] {
+///     let mut tok = sent.create_token(form).unwrap();
+///     tok.set_lemma(lemma);
+///     tok.set_upos(upos);
+///     sent.add_token(tok);
+/// }
+/// doc.add_sentence(sent).unwrap();
+/// corp.add_doc(doc).unwrap();
+///
+/// let query = Query::parse(r#"[lemma="go"] [upos="ADP"]? [upos="NOUN"]"#).unwrap();
+/// let matches = corp.query(&query);
+/// assert_eq!(matches.len(), 1);
+/// assert_eq!(matches[0].range(), 1..4);
+///
+/// assert!(Query::parse(r#"[lemma="go""#).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Query {
+    sequence: Vec<Element>,
+    within: Vec<(String, Regex)>,
+}
+
+/// A match of a [`Query`]: a range of tokens of a source sentence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Match {
+    doc_id: u32,
+    sentence_id: u32,
+    start: usize,
+    end: usize,
+}
+
+impl Match {
+    pub(crate) const fn new(doc_id: u32, sentence_id: u32, range: Range<usize>) -> Self {
+        Self {
+            doc_id,
+            sentence_id,
+            start: range.start,
+            end: range.end,
+        }
+    }
+
+    pub const fn doc_id(&self) -> u32 {
+        self.doc_id
+    }
+
+    pub const fn sentence_id(&self) -> u32 {
+        self.sentence_id
+    }
+
+    /// Returns the range of matched tokens as indices into
+    /// [`Sentence::tokens()`](crate::Sentence::tokens), the end is exclusive.
+    pub const fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns the matched tokens, `None` if the match does not belong to the corpus.
+    pub fn tokens<'a>(&self, corpus: &'a Corpus) -> Option<&'a [Token]> {
+        corpus
+            .doc(self.doc_id)?
+            .sentence(self.sentence_id)?
+            .tokens
+            .get(self.range())
+    }
+}
+
+/// A condition on the attributes of a single token.
+#[derive(Debug, Clone)]
+pub(crate) enum Condition {
+    Any,
+    Attr(TokenField, Regex, bool),
+    Not(Box<Condition>),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+}
+
+impl Condition {
+    pub(crate) fn matches(&self, tok: &Token) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Attr(field, re, negated) => {
+                re.is_match(tok.get(*field).unwrap_or_default()) != *negated
+            }
+            Self::Not(cond) => !cond.matches(tok),
+            Self::And(conds) => conds.iter().all(|c| c.matches(tok)),
+            Self::Or(conds) => conds.iter().any(|c| c.matches(tok)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Pattern {
+    Token(Condition),
+    Group(Vec<Vec<Element>>),
+}
+
+#[derive(Debug, Clone)]
+struct Element {
+    pattern: Pattern,
+    min: usize,
+    max: Option<usize>,
+}
+
+/// Recursive descent parser of the query syntax.
+pub(crate) struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub(crate) const fn new(src: &'a str) -> Self {
+        Self { src, pos: 0 }
+    }
+
+    pub(crate) fn error(&self, msg: &str) -> CorporeumError {
+        CorporeumError::InvalidQuery(self.pos, msg.to_owned())
+    }
+
+    pub(crate) fn skip_ws(&mut self) {
+        let rest = &self.src[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    pub(crate) fn peek(&mut self) -> Option<char> {
+        self.skip_ws();
+        self.src[self.pos..].chars().next()
+    }
+
+    pub(crate) fn at_end(&mut self) -> bool {
+        self.peek().is_none()
+    }
+
+    /// Consumes `token` if the remaining input starts with it.
+    pub(crate) fn eat(&mut self, token: &str) -> bool {
+        self.skip_ws();
+        if self.src[self.pos..].starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn expect(&mut self, token: &str) -> Result<(), CorporeumError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected '{token}'")))
+        }
+    }
+
+    /// Parses an identifier made of alphanumeric characters, `_`, `-` and `.`.
+    pub(crate) fn ident(&mut self) -> Result<&'a str, CorporeumError> {
+        self.skip_ws();
+        let rest = &self.src[self.pos..];
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '.')))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("Expected a name"));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    /// Parses a variable name made of alphanumeric characters and `_`.
+    pub(crate) fn var(&mut self) -> Result<&'a str, CorporeumError> {
+        self.skip_ws();
+        let rest = &self.src[self.pos..];
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("Expected a variable name"));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    /// Returns the input up to `delim` and consumes both.
+    pub(crate) fn until(&mut self, delim: &str) -> Result<&'a str, CorporeumError> {
+        let rest = &self.src[self.pos..];
+        let len = rest
+            .find(delim)
+            .ok_or_else(|| self.error(&format!("Expected '{delim}'")))?;
+        self.pos += len + delim.len();
+        Ok(&rest[..len])
+    }
+
+    fn number(&mut self) -> Result<usize, CorporeumError> {
+        self.skip_ws();
+        let rest = &self.src[self.pos..];
+        let len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let num = rest[..len]
+            .parse()
+            .map_err(|_| self.error("Expected a number"))?;
+        self.pos += len;
+        Ok(num)
+    }
+
+    /// Parses a double-quoted string, `\"` is unescaped, other escapes are kept
+    /// for the regular expression.
+    pub(crate) fn string(&mut self) -> Result<String, CorporeumError> {
+        self.expect("\"")?;
+        let mut value = String::new();
+        let mut chars = self.src[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(value);
+                }
+                '\\' => match chars.next() {
+                    Some((_, '"')) => value.push('"'),
+                    Some((_, c)) => {
+                        value.push('\\');
+                        value.push(c);
+                    }
+                    None => break,
+                },
+                _ => value.push(c),
+            }
+        }
+        self.pos = self.src.len();
+        Err(self.error("Unterminated string"))
+    }
+
+    /// Parses a quoted regular expression with an optional `%c` flag
+    /// and compiles it to match whole values.
+    pub(crate) fn regex(&mut self) -> Result<Regex, CorporeumError> {
+        let start = self.pos;
+        let value = self.string()?;
+        let case_insensitive = self.src[self.pos..].starts_with("%c");
+        if case_insensitive {
+            self.pos += 2;
+        }
+
+        RegexBuilder::new(&format!("^(?:{value})$"))
+            .case_insensitive(case_insensitive)
+            .build()
+            .map_err(|e| CorporeumError::InvalidQuery(start, e.to_string()))
+    }
+
+    /// Parses a token condition in brackets, e.g. `[upos="NOUN" & !lemma="be"]`.
+    pub(crate) fn token_condition(&mut self) -> Result<Condition, CorporeumError> {
+        self.expect("[")?;
+        if self.eat("]") {
+            return Ok(Condition::Any);
+        }
+        let cond = self.or_condition()?;
+        self.expect("]")?;
+        Ok(cond)
+    }
+
+    fn or_condition(&mut self) -> Result<Condition, CorporeumError> {
+        let mut conds = vec![self.and_condition()?];
+        while self.eat("|") {
+            conds.push(self.and_condition()?);
+        }
+        Ok(if conds.len() == 1 {
+            conds.remove(0)
+        } else {
+            Condition::Or(conds)
+        })
+    }
+
+    fn and_condition(&mut self) -> Result<Condition, CorporeumError> {
+        let mut conds = vec![self.unary_condition()?];
+        while self.eat("&") {
+            conds.push(self.unary_condition()?);
+        }
+        Ok(if conds.len() == 1 {
+            conds.remove(0)
+        } else {
+            Condition::And(conds)
+        })
+    }
+
+    fn unary_condition(&mut self) -> Result<Condition, CorporeumError> {
+        if self.eat("!") {
+            return Ok(Condition::Not(Box::new(self.unary_condition()?)));
+        }
+        if self.eat("(") {
+            let cond = self.or_condition()?;
+            self.expect(")")?;
+            return Ok(cond);
+        }
+
+        let start = self.pos;
+        let name = self.ident()?;
+        let field = if name.eq_ignore_ascii_case("word") {
+            TokenField::Form
+        } else {
+            TokenField::from_str(name).map_err(|_| {
+                CorporeumError::InvalidQuery(start, format!("Unknown attribute '{name}'"))
+            })?
+        };
+
+        let negated = if self.eat("!=") {
+            true
+        } else {
+            self.expect("=")?;
+            false
+        };
+        Ok(Condition::Attr(field, self.regex()?, negated))
+    }
+
+    fn sequence(&mut self) -> Result<Vec<Element>, CorporeumError> {
+        let mut elements = Vec::new();
+        while matches!(self.peek(), Some('[' | '"' | '(')) {
+            elements.push(self.element()?);
+        }
+        if elements.is_empty() {
+            return Err(self.error("Expected a token pattern"));
+        }
+        Ok(elements)
+    }
+
+    fn element(&mut self) -> Result<Element, CorporeumError> {
+        let pattern = match self.peek() {
+            Some('"') => Pattern::Token(Condition::Attr(TokenField::Form, self.regex()?, false)),
+            Some('(') => {
+                self.expect("(")?;
+                let mut alternatives = vec![self.sequence()?];
+                while self.eat("|") {
+                    alternatives.push(self.sequence()?);
+                }
+                self.expect(")")?;
+                Pattern::Group(alternatives)
+            }
+            _ => Pattern::Token(self.token_condition()?),
+        };
+
+        let (min, max) = if self.eat("?") {
+            (0, Some(1))
+        } else if self.eat("*") {
+            (0, None)
+        } else if self.eat("+") {
+            (1, None)
+        } else if self.eat("{") {
+            let min = self.number()?;
+            let max = if self.eat(",") {
+                if self.peek() == Some('}') {
+                    None
+                } else {
+                    Some(self.number()?)
+                }
+            } else {
+                Some(min)
+            };
+            if max.is_some_and(|max| max < min) {
+                return Err(self.error("Invalid repetition range"));
+            }
+            self.expect("}")?;
+            (min, max)
+        } else {
+            (1, Some(1))
+        };
+
+        Ok(Element { pattern, min, max })
+    }
+
+    fn within(&mut self) -> Result<Vec<(String, Regex)>, CorporeumError> {
+        self.expect("<")?;
+        if self.ident()? != "s" {
+            return Err(self.error("Only sentences ('s') are supported in 'within'"));
+        }
+
+        let mut attrs = Vec::new();
+        while !self.eat("/>") {
+            let name = self.ident()?.to_owned();
+            self.expect("=")?;
+            attrs.push((name, self.regex()?));
+        }
+        Ok(attrs)
+    }
+}
+
+impl Query {
+    /// Parses a query, see [`Query`] for the syntax.
+    ///
+    /// # Errors
+    /// This will return [`CorporeumError::InvalidQuery`] with the offset of the
+    /// problem if the query is malformed, names an unknown attribute or
+    /// contains an invalid regular expression.
+    pub fn parse(query: &str) -> Result<Self, CorporeumError> {
+        let mut parser = Parser::new(query);
+        let sequence = parser.sequence()?;
+
+        let within = if parser.eat("within") {
+            parser.within()?
+        } else {
+            Vec::new()
+        };
+
+        if !parser.at_end() {
+            return Err(parser.error("Unexpected input"));
+        }
+        Ok(Self { sequence, within })
+    }
+
+    /// Returns `true` if the sentence satisfies the `within` clause of this query.
+    pub fn accepts_sentence<T>(&self, sent: &Sentence<T>) -> bool {
+        self.within.iter().all(|(name, re)| {
+            let value = match name.as_str() {
+                "lang" => Some(sent.lang.as_str()),
+                "speaker" => sent.speaker.as_deref(),
+                _ => sent.meta(name),
+            };
+            re.is_match(value.unwrap_or_default())
+        })
+    }
+
+    /// Returns the condition every match must start with, if the query starts
+    /// with a single required token pattern.
+    pub(crate) fn first_condition(&self) -> Option<&Condition> {
+        match self.sequence.first()? {
+            Element {
+                pattern: Pattern::Token(cond),
+                min,
+                ..
+            } if *min > 0 => Some(cond),
+            _ => None,
+        }
+    }
+
+    /// Finds all matches of this query in a sentence, as ranges of token indices.
+    ///
+    /// For every start position only the longest match is reported, so matches
+    /// starting at different tokens may overlap. Empty matches are skipped.
+    pub fn find_in<T>(&self, sent: &Sentence<T>) -> Vec<Range<usize>> {
+        if !self.accepts_sentence(sent) {
+            return Vec::new();
+        }
+
+        let tokens = &sent.tokens;
+        (0..tokens.len())
+            .filter_map(|start| {
+                let end = sequence_ends(&self.sequence, tokens, start)
+                    .into_iter()
+                    .next_back()?;
+                (end > start).then_some(start..end)
+            })
+            .collect()
+    }
+}
+
+fn sequence_ends(sequence: &[Element], tokens: &[Token], start: usize) -> BTreeSet<usize> {
+    let mut positions = BTreeSet::from([start]);
+    for element in sequence {
+        positions = positions
+            .into_iter()
+            .flat_map(|pos| element_ends(element, tokens, pos))
+            .collect();
+        if positions.is_empty() {
+            break;
+        }
+    }
+    positions
+}
+
+fn element_ends(element: &Element, tokens: &[Token], start: usize) -> BTreeSet<usize> {
+    let mut ends = BTreeSet::new();
+    if element.min == 0 {
+        ends.insert(start);
+    }
+
+    // no pattern consumes more than one token per repetition beyond the sentence end
+    let max = element
+        .max
+        .unwrap_or(usize::MAX)
+        .min(tokens.len() - start + 1);
+    let mut current = BTreeSet::from([start]);
+    for i in 1..=max {
+        current = current
+            .into_iter()
+            .flat_map(|pos| pattern_ends(&element.pattern, tokens, pos))
+            .collect();
+        if current.is_empty() {
+            break;
+        }
+        if i >= element.min {
+            ends.extend(&current);
+        }
+    }
+    ends
+}
+
+fn pattern_ends(pattern: &Pattern, tokens: &[Token], start: usize) -> BTreeSet<usize> {
+    match pattern {
+        Pattern::Token(cond) => tokens
+            .get(start)
+            .filter(|tok| cond.matches(tok))
+            .map(|_| start + 1)
+            .into_iter()
+            .collect(),
+        Pattern::Group(alternatives) => alternatives
+            .iter()
+            .flat_map(|seq| sequence_ends(seq, tokens, start))
+            .collect(),
+    }
+}
+
+impl Corpus {
+    /// Finds all matches of a query in the source sentences of the corpus,
+    /// see [`Query::find_in()`].
+    pub fn query(&self, query: &Query) -> Vec<Match> {
+        let mut matches = Vec::new();
+        for doc in &self.documents {
+            for sent in &doc.sentences {
+                matches.extend(
+                    query
+                        .find_in(sent)
+                        .into_iter()
+                        .map(|range| Match::new(doc.id, sent.id, range)),
+                );
+            }
+        }
+        matches
+    }
+}
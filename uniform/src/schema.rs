@@ -1,9 +1,10 @@
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::marker::PhantomData;
 
 /// Represents a text corpus.
-#[derive(Deserialize, Serialize, Debug, Default)]
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq)]
 #[serde(rename = "corpus")]
 pub struct Corpus {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -16,7 +17,7 @@ pub struct Corpus {
 ///
 /// This structure __cannot__ be created by the user, to add
 /// metadata to a [`Corpus`](Corpus), use [`add_metadata()`](Corpus::add_metadata) instead.
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct Metadata {
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub(crate) authors: Vec<Author>,
@@ -28,6 +29,8 @@ pub struct Metadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) modified: Option<i64>,
     pub(crate) name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub(crate) speakers: Vec<Speaker>,
     #[serde(rename = "version")]
     pub(crate) version: u16,
 }
@@ -36,31 +39,48 @@ pub struct Metadata {
 ///
 /// This structure __cannot__ be created by the user, to add an `Author`
 /// to [`Metadata`](Metadata), use [`add_author()`](Metadata::add_author) instead.
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct Author {
     pub(crate) first_name: String,
     pub(crate) last_name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) mail: Option<String>,
 }
+/// Represents a speaker of spoken or transcribed documents.
+///
+/// This structure __cannot__ be created by the user, to add a `Speaker`
+/// to [`Metadata`](Metadata), use [`add_speaker()`](Metadata::add_speaker) instead.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Speaker {
+    pub(crate) id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) age: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) gender: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) dialect: Option<String>,
+}
+
 /// Represents a document.
 ///
 /// This structure __cannot__ be created by the user, to add a `Document`
 /// to a [`Corpus`](Corpus), use [`add_doc()`](Corpus::add_doc) instead.
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct Document {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) description: Option<String>,
     pub(crate) id: u32,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub(crate) meta: BTreeMap<String, String>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub(crate) sentences: Vec<Sentence<Source>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) source: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Source;
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Target;
 
 /// Represents a sentence.
@@ -68,13 +88,22 @@ pub struct Target;
 /// This structure __cannot__ be created by the user, to add a `Sentence`
 /// to a [`Document`](Document), use [`create_sentence()`](Document::create_sentence) and
 /// [`add_sentence()`](Document::add_sentence) instead.
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct Sentence<Type> {
     pub(crate) t: PhantomData<Type>,
     pub(crate) id: u32,
     // TODO enum - could be either 'source' or 'target'
     pub(crate) lang: String, // TODO features, labels
     // pub(crate) sentence_type: SentenceType, // language identifier
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub(crate) meta: BTreeMap<String, String>,
+    // start and end offsets in milliseconds, e.g. for subtitles or transcriptions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) start: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) end: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) speaker: Option<String>,
     pub(crate) tokens: Vec<Token>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub(crate) translations: Vec<Sentence<Target>>,
@@ -85,7 +114,7 @@ pub struct Sentence<Type> {
 /// This structure __cannot__ be created by the user, to add a `Token`
 /// to a [`Sentence`](Sentence), use [`create_token()`](Sentence::create_token) and
 /// [`add_token()`](Sentence::add_token) instead.
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct Token {
     pub(crate) id: u32,
     pub(crate) form: String,
@@ -105,4 +134,12 @@ pub struct Token {
     pub(crate) deps: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) misc: Option<String>, // this is here only for compatibility reasons with CoNLL-U
+    // interlinear morpheme gloss, e.g. "they-OBL-GEN"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) gloss: Option<String>,
+    // start and end offsets in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) start: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) end: Option<u64>,
 }
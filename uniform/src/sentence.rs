@@ -1,7 +1,12 @@
+use crate::integrity::{free_ids, next_free};
 use crate::schema::Sentence;
 use crate::schema::Source;
 use crate::schema::Target;
 use crate::schema::Token;
+use crate::CorporeumError;
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::marker::PhantomData;
 
 impl Sentence<Source> {
@@ -10,6 +15,10 @@ impl Sentence<Source> {
             t: PhantomData,
             id,
             lang: lang.to_string(),
+            meta: BTreeMap::new(),
+            start: None,
+            end: None,
+            speaker: None,
             tokens: Vec::new(),
             translations: Vec::new(),
         }
@@ -19,8 +28,15 @@ impl Sentence<Source> {
     ///
     /// To add this translation to a [`Sentence<Source>`](Self), use
     /// [`add_translation()`](Self::add_translation).
-    pub fn create_translation(&self, lang: &str) -> Sentence<Target> {
-        Sentence::<Target>::new(self.translations.last().map_or(0, |t| t.id + 1), lang)
+    ///
+    /// # Errors
+    /// This will return an error if a translation already has the ID `u32::MAX`.
+    pub fn create_translation(&self, lang: &str) -> Result<Sentence<Target>, CorporeumError> {
+        let mut ids = free_ids(self.translations.iter().map(|t| t.id).max());
+        Ok(Sentence::<Target>::new(
+            next_free(&mut ids, "translation")?,
+            lang,
+        ))
     }
 
     /// Add a translation.
@@ -33,8 +49,30 @@ impl Sentence<Source> {
         self.translations.push(translation);
     }
 
-    pub fn remove_translation(&mut self, id: usize) {
-        self.translations.remove(id);
+    /// Removes a translation by its ID.
+    ///
+    /// # Errors
+    /// This will return an error if the specified translation does not exist.
+    pub fn remove_translation(&mut self, id: u32) -> Result<(), CorporeumError> {
+        let Some(pos) = self.translations.iter().position(|trans| trans.id == id) else {
+            return Err(CorporeumError::ElementNotFound(format!(
+                "Translation with ID {id} does not exist"
+            )));
+        };
+
+        self.translations.remove(pos);
+        Ok(())
+    }
+
+    /// Reassigns contiguous IDs starting at `0` to the translations and the
+    /// tokens of this sentence and of its translations, see
+    /// [`renumber_tokens()`](Self::renumber_tokens).
+    pub fn renumber(&mut self) {
+        for (id, trans) in (0..).zip(&mut self.translations) {
+            trans.id = id;
+            trans.renumber_tokens();
+        }
+        self.renumber_tokens();
     }
 
     pub fn translation(&self, id: u32) -> Option<&Sentence<Target>> {
@@ -60,6 +98,10 @@ impl Sentence<Target> {
             t: PhantomData,
             id,
             lang: lang.to_string(),
+            meta: BTreeMap::new(),
+            start: None,
+            end: None,
+            speaker: None,
             tokens: Vec::new(),
             translations: Vec::new(),
         }
@@ -70,8 +112,12 @@ impl<T> Sentence<T> {
     /// Creates a new [`Token`](crate::Token) with a proper unique ID.
     ///
     /// To add this token to this Sentence, use [`add_token`](Self::add_token).
-    pub fn create_token(&self, form: &str) -> Token {
-        Token::new(self.tokens.last().map_or(0, |t| t.id + 1), form)
+    ///
+    /// # Errors
+    /// This will return an error if a token already has the ID `u32::MAX`.
+    pub fn create_token(&self, form: &str) -> Result<Token, CorporeumError> {
+        let mut ids = free_ids(self.tokens.iter().map(|t| t.id).max());
+        Ok(Token::new(next_free(&mut ids, "token")?, form))
     }
 
     /// Add a token to this sentence.
@@ -85,20 +131,134 @@ impl<T> Sentence<T> {
         self.tokens.push(token);
     }
 
+    /// Reassigns contiguous token IDs starting at `0` in sentence order and
+    /// rewrites heads and enhanced dependencies to follow the tokens.
+    ///
+    /// Heads pointing to a duplicate ID follow its first token, heads pointing
+    /// to an ID no token has are dropped.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, Tokenizer};
+    ///
+    /// let mut corp = Corpus::new();
+    /// corp.import_text("Dogs bark.", &Tokenizer::new("en")).unwrap();
+    /// let sent = corp.doc_mut(0).unwrap().sentence_mut(0).unwrap();
+    /// sent.token_mut(1).unwrap().set_head("0");
+    /// sent.token_mut(2).unwrap().set_head("9");
+    ///
+    /// sent.renumber_tokens();
+    /// assert_eq!(sent.tokens()[1].head(), Some("0"));
+    /// assert_eq!(sent.tokens()[2].head(), None);
+    /// ```
+    pub fn renumber_tokens(&mut self) {
+        let tokens = self
+            .tokens
+            .drain(..)
+            .map(|tok| (Some(tok.id), tok))
+            .collect();
+        self.replace_tokens(tokens, &HashMap::new());
+    }
+
     pub const fn tokens(&self) -> &Vec<Token> {
         &self.tokens
     }
 
+    /// Fetch a token by its ID and return a reference to it if it exists.
+    pub fn token(&self, id: u32) -> Option<&Token> {
+        self.tokens.iter().find(|tok| tok.id == id)
+    }
+
+    /// Fetch a token by its ID and return a mutable reference to it if it exists.
+    pub fn token_mut(&mut self, id: u32) -> Option<&mut Token> {
+        self.tokens.iter_mut().find(|tok| tok.id == id)
+    }
+
     pub const fn sentence_id(&self) -> u32 {
         self.id
     }
 
+    /// Returns the language of this sentence.
+    pub fn lang(&self) -> &str {
+        &self.lang
+    }
+
+    /// Returns the value of a metadata entry of this sentence.
+    pub fn meta(&self, key: &str) -> Option<&str> {
+        self.meta.get(key).map(String::as_str)
+    }
+
+    /// Returns all metadata entries of this sentence.
+    pub const fn meta_entries(&self) -> &BTreeMap<String, String> {
+        &self.meta
+    }
+
+    /// Sets a metadata entry of this sentence, replacing the previous value.
+    pub fn set_meta(&mut self, key: &str, value: &str) {
+        self.meta.insert(key.to_owned(), value.to_owned());
+    }
+
+    /// Removes a metadata entry from this sentence.
+    pub fn remove_meta(&mut self, key: &str) {
+        self.meta.remove(key);
+    }
+
+    /// Returns the start offset of this sentence in milliseconds.
+    pub const fn start(&self) -> Option<u64> {
+        self.start
+    }
+
+    /// Returns the end offset of this sentence in milliseconds.
+    pub const fn end(&self) -> Option<u64> {
+        self.end
+    }
+
+    /// Sets the start and end offsets of this sentence in milliseconds.
+    pub fn set_timing(&mut self, start: u64, end: u64) {
+        self.start = Some(start);
+        self.end = Some(end);
+    }
+
+    /// Removes the start and end offsets of this sentence.
+    pub fn remove_timing(&mut self) {
+        self.start = None;
+        self.end = None;
+    }
+
+    /// Returns the ID of the speaker of this sentence.
+    ///
+    /// Details about the speaker can be found in [`Metadata::speaker()`](crate::Metadata::speaker).
+    pub fn speaker(&self) -> Option<&str> {
+        self.speaker.as_deref()
+    }
+
+    /// Sets the ID of the speaker of this sentence.
+    pub fn set_speaker(&mut self, speaker: &str) {
+        self.speaker = Some(speaker.to_owned());
+    }
+
+    /// Removes the speaker of this sentence.
+    pub fn remove_speaker(&mut self) {
+        self.speaker = None;
+    }
+
+    /// Joins the tokens of this sentence into text.
+    ///
+    /// Tokens are separated by a single space unless they are marked with
+    /// `SpaceAfter=No` (see [`Token::space_after()`](crate::Token::space_after)).
     pub fn to_text(&self) -> String {
-        return self
-            .tokens()
-            .iter()
-            .map(|t| t.form())
-            .collect::<Vec<&str>>()
-            .join(" ");
+        join_tokens(&self.tokens)
+    }
+}
+
+/// Joins token forms, honoring `SpaceAfter=No`.
+pub(crate) fn join_tokens<B: Borrow<Token>>(tokens: &[B]) -> String {
+    let mut text = String::new();
+    for (i, tok) in tokens.iter().map(Borrow::borrow).enumerate() {
+        text.push_str(tok.form());
+        if tok.space_after() && i + 1 < tokens.len() {
+            text.push(' ');
+        }
     }
+    text
 }
@@ -0,0 +1,62 @@
+use crate::schema::Speaker;
+
+impl Speaker {
+    pub(crate) fn new(id: &str) -> Self {
+        Self {
+            id: id.to_owned(),
+            age: None,
+            gender: None,
+            dialect: None,
+        }
+    }
+
+    /// Returns the ID of this speaker, as referenced by [`Sentence::speaker()`](crate::Sentence::speaker).
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the age of this speaker.
+    pub const fn age(&self) -> Option<u16> {
+        self.age
+    }
+
+    /// Sets the age of this speaker.
+    pub fn set_age(&mut self, age: u16) {
+        self.age = Some(age);
+    }
+
+    /// Removes the age of this speaker.
+    pub fn remove_age(&mut self) {
+        self.age = None;
+    }
+
+    /// Returns the gender of this speaker.
+    pub fn gender(&self) -> Option<&str> {
+        self.gender.as_deref()
+    }
+
+    /// Sets the gender of this speaker.
+    pub fn set_gender(&mut self, gender: &str) {
+        self.gender = Some(gender.to_owned());
+    }
+
+    /// Removes the gender of this speaker.
+    pub fn remove_gender(&mut self) {
+        self.gender = None;
+    }
+
+    /// Returns the dialect of this speaker.
+    pub fn dialect(&self) -> Option<&str> {
+        self.dialect.as_deref()
+    }
+
+    /// Sets the dialect of this speaker.
+    pub fn set_dialect(&mut self, dialect: &str) {
+        self.dialect = Some(dialect.to_owned());
+    }
+
+    /// Removes the dialect of this speaker.
+    pub fn remove_dialect(&mut self) {
+        self.dialect = None;
+    }
+}
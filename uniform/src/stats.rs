@@ -0,0 +1,346 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::{schema::Corpus, CorporeumError, TokenField};
+
+/// Distribution of sentence lengths in tokens.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LengthStats {
+    min: usize,
+    max: usize,
+    mean: f64,
+    median: f64,
+    /// number of sentences per length
+    histogram: BTreeMap<usize, usize>,
+}
+
+impl LengthStats {
+    #[allow(clippy::cast_precision_loss)]
+    fn new(mut lengths: Vec<usize>) -> Self {
+        if lengths.is_empty() {
+            return Self::default();
+        }
+
+        lengths.sort_unstable();
+        let n = lengths.len();
+        let median = if n % 2 == 0 {
+            (lengths[n / 2 - 1] + lengths[n / 2]) as f64 / 2.0
+        } else {
+            lengths[n / 2] as f64
+        };
+
+        let mut histogram = BTreeMap::new();
+        for &len in &lengths {
+            *histogram.entry(len).or_default() += 1;
+        }
+
+        Self {
+            min: lengths[0],
+            max: lengths[n - 1],
+            mean: lengths.iter().sum::<usize>() as f64 / n as f64,
+            median,
+            histogram,
+        }
+    }
+
+    pub const fn min(&self) -> usize {
+        self.min
+    }
+
+    pub const fn max(&self) -> usize {
+        self.max
+    }
+
+    pub const fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    pub const fn median(&self) -> f64 {
+        self.median
+    }
+
+    /// Returns the number of sentences for every sentence length.
+    pub const fn histogram(&self) -> &BTreeMap<usize, usize> {
+        &self.histogram
+    }
+}
+
+/// Summary statistics of a corpus, see [`Corpus::stats()`].
+///
+/// The report is printed as text with [`Display`](fmt::Display) and
+/// as JSON with [`to_json()`](Self::to_json).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CorpusStats {
+    documents: usize,
+    sentences: usize,
+    tokens: usize,
+    types: usize,
+    type_token_ratio: f64,
+    sentence_length: LengthStats,
+    /// source sentences per language
+    languages: BTreeMap<String, usize>,
+    translations: usize,
+    translated_sentences: usize,
+    translation_coverage: f64,
+    /// translations per language
+    translation_languages: BTreeMap<String, usize>,
+    /// tokens with the field set, per field name
+    field_coverage: BTreeMap<String, usize>,
+    upos: BTreeMap<String, usize>,
+    deprel: BTreeMap<String, usize>,
+    /// tokens per feature name
+    features: BTreeMap<String, usize>,
+}
+
+impl CorpusStats {
+    pub const fn documents(&self) -> usize {
+        self.documents
+    }
+
+    /// Returns the number of source sentences.
+    pub const fn sentences(&self) -> usize {
+        self.sentences
+    }
+
+    /// Returns the number of tokens of source sentences.
+    pub const fn tokens(&self) -> usize {
+        self.tokens
+    }
+
+    /// Returns the number of distinct token forms.
+    pub const fn types(&self) -> usize {
+        self.types
+    }
+
+    pub const fn type_token_ratio(&self) -> f64 {
+        self.type_token_ratio
+    }
+
+    pub const fn sentence_length(&self) -> &LengthStats {
+        &self.sentence_length
+    }
+
+    /// Returns the number of source sentences per language.
+    pub const fn languages(&self) -> &BTreeMap<String, usize> {
+        &self.languages
+    }
+
+    /// Returns the total number of translations.
+    pub const fn translations(&self) -> usize {
+        self.translations
+    }
+
+    /// Returns the number of source sentences with at least one translation.
+    pub const fn translated_sentences(&self) -> usize {
+        self.translated_sentences
+    }
+
+    /// Returns the share of source sentences with at least one translation.
+    pub const fn translation_coverage(&self) -> f64 {
+        self.translation_coverage
+    }
+
+    /// Returns the number of translations per language.
+    pub const fn translation_languages(&self) -> &BTreeMap<String, usize> {
+        &self.translation_languages
+    }
+
+    /// Returns the number of tokens with the field set.
+    pub fn field_coverage(&self, field: TokenField) -> usize {
+        self.field_coverage
+            .get(field.name())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the UPOS tags with their frequencies.
+    pub const fn upos(&self) -> &BTreeMap<String, usize> {
+        &self.upos
+    }
+
+    /// Returns the dependency relations with their frequencies.
+    pub const fn deprel(&self) -> &BTreeMap<String, usize> {
+        &self.deprel
+    }
+
+    /// Returns the feature names (e.g. `Case`) with the number of tokens having them.
+    pub const fn features(&self) -> &BTreeMap<String, usize> {
+        &self.features
+    }
+
+    /// Serializes the report as pretty-printed JSON.
+    ///
+    /// # Errors
+    /// This will return an error if serialization fails.
+    pub fn to_json(&self) -> Result<String, CorporeumError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Divides `part` by `total`, `0.0` if `total` is zero.
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn ratio(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+fn write_inventory(
+    f: &mut fmt::Formatter<'_>,
+    title: &str,
+    counts: &BTreeMap<String, usize>,
+) -> fmt::Result {
+    if counts.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(f, "{title}:")?;
+    for (label, count) in counts {
+        writeln!(f, "  {label:<16} {count}")?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for CorpusStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Documents:        {}", self.documents)?;
+        writeln!(f, "Sentences:        {}", self.sentences)?;
+        writeln!(f, "Tokens:           {}", self.tokens)?;
+        writeln!(f, "Types:            {}", self.types)?;
+        writeln!(f, "Type/token ratio: {:.4}", self.type_token_ratio)?;
+        writeln!(
+            f,
+            "Sentence length:  min {}, max {}, mean {:.2}, median {:.1}",
+            self.sentence_length.min,
+            self.sentence_length.max,
+            self.sentence_length.mean,
+            self.sentence_length.median
+        )?;
+        writeln!(
+            f,
+            "Translations:     {} ({} sentences, {:.1} %)",
+            self.translations,
+            self.translated_sentences,
+            self.translation_coverage * 100.0
+        )?;
+
+        write_inventory(f, "Languages", &self.languages)?;
+        write_inventory(f, "Translation languages", &self.translation_languages)?;
+
+        writeln!(f, "Annotation coverage:")?;
+        for (field, count) in &self.field_coverage {
+            writeln!(
+                f,
+                "  {field:<16} {count} ({:.1} %)",
+                ratio(*count as u64, self.tokens as u64) * 100.0
+            )?;
+        }
+
+        write_inventory(f, "UPOS", &self.upos)?;
+        write_inventory(f, "DEPREL", &self.deprel)?;
+        write_inventory(f, "Features", &self.features)
+    }
+}
+
+impl Corpus {
+    /// Computes summary statistics of the corpus.
+    ///
+    /// Counts refer to source sentences and their tokens, translations are
+    /// only counted as such.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, TokenField, Tokenizer};
+    ///
+    /// let mut corp = Corpus::new();
+    /// let tokenizer = Tokenizer::new("en");
+    /// corp.import_text("The cat sat. The cat slept well.", &tokenizer).unwrap();
+    ///
+    /// let stats = corp.stats();
+    /// assert_eq!((stats.sentences(), stats.tokens(), stats.types()), (2, 9, 6));
+    /// assert_eq!(stats.sentence_length().median(), 4.5);
+    /// assert_eq!(stats.languages()["en"], 2);
+    /// assert_eq!(stats.field_coverage(TokenField::Lemma), 0);
+    ///
+    /// assert!(stats.to_string().starts_with("Documents:        1\n"));
+    /// assert!(stats.to_json().unwrap().contains("\"type_token_ratio\""));
+    /// ```
+    pub fn stats(&self) -> CorpusStats {
+        let mut types = HashSet::new();
+        let mut lengths = Vec::new();
+        let mut languages: BTreeMap<String, usize> = BTreeMap::new();
+        let mut translations = 0;
+        let mut translated_sentences = 0;
+        let mut translation_languages: BTreeMap<String, usize> = BTreeMap::new();
+        let annotations: Vec<TokenField> = TokenField::ALL
+            .into_iter()
+            .filter(|&f| f != TokenField::Form)
+            .collect();
+        let mut field_coverage: BTreeMap<String, usize> = annotations
+            .iter()
+            .map(|f| (f.name().to_owned(), 0))
+            .collect();
+        let mut upos: BTreeMap<String, usize> = BTreeMap::new();
+        let mut deprel: BTreeMap<String, usize> = BTreeMap::new();
+        let mut features: BTreeMap<String, usize> = BTreeMap::new();
+
+        for sent in self.documents.iter().flat_map(|d| &d.sentences) {
+            lengths.push(sent.tokens.len());
+            *languages.entry(sent.lang.clone()).or_default() += 1;
+
+            translations += sent.translations.len();
+            if !sent.translations.is_empty() {
+                translated_sentences += 1;
+            }
+            for trans in &sent.translations {
+                *translation_languages.entry(trans.lang.clone()).or_default() += 1;
+            }
+
+            for tok in &sent.tokens {
+                types.insert(tok.form.as_str());
+
+                for field in &annotations {
+                    if tok.get(*field).is_some() {
+                        *field_coverage.entry(field.name().to_owned()).or_default() += 1;
+                    }
+                }
+                if let Some(tag) = &tok.upos {
+                    *upos.entry(tag.clone()).or_default() += 1;
+                }
+                if let Some(rel) = &tok.deprel {
+                    *deprel.entry(rel.clone()).or_default() += 1;
+                }
+                for feat in tok.feats.iter().flat_map(|f| f.split('|')) {
+                    if let Some((name, _)) = feat.split_once('=') {
+                        *features.entry(name.to_owned()).or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        let tokens = lengths.iter().sum();
+        let sentences = lengths.len();
+
+        CorpusStats {
+            documents: self.documents.len(),
+            sentences,
+            tokens,
+            types: types.len(),
+            type_token_ratio: ratio(types.len() as u64, tokens as u64),
+            sentence_length: LengthStats::new(lengths),
+            languages,
+            translations,
+            translated_sentences,
+            translation_coverage: ratio(translated_sentences as u64, sentences as u64),
+            translation_languages,
+            field_coverage,
+            upos,
+            deprel,
+            features,
+        }
+    }
+}
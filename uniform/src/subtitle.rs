@@ -0,0 +1,417 @@
+use std::io::{BufRead, Write};
+
+use crate::{
+    integrity::{free_ids, next_free},
+    schema::{Corpus, Document, Sentence, Source},
+    text::TextToken,
+    CorporeumError, Tokenizer,
+};
+
+/// Sentence metadata key holding the identifier of the cue (SRT counter or WebVTT cue ID).
+pub const CUE_ID: &str = "cue_id";
+/// Sentence metadata key holding WebVTT cue settings, e.g. `align:start line:0`.
+pub const CUE_SETTINGS: &str = "cue_settings";
+
+/// Subtitle file format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtitleFormat {
+    /// SubRip (`.srt`).
+    Srt,
+    /// Web Video Text Tracks (`.vtt`).
+    WebVtt,
+}
+
+/// How cues are turned into sentences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segmentation {
+    /// Every cue becomes one sentence.
+    Cue,
+    /// The text of all cues is split into sentences by the tokenizer. A sentence
+    /// starts at the start of the cue containing its first token and ends at the
+    /// end of the cue containing its last token.
+    Sentence,
+}
+
+struct Cue {
+    id: Option<String>,
+    start: u64,
+    end: u64,
+    settings: Option<String>,
+    speaker: Option<String>,
+    text: String,
+}
+
+impl Corpus {
+    /// Reads an SRT or WebVTT file into a new document and adds it to the corpus.
+    /// Returns the ID of the new document.
+    ///
+    /// Sentences get their start and end timestamps from the cues, see
+    /// [`Sentence::start()`](crate::Sentence::start). With [`Segmentation::Cue`],
+    /// the cue identifier and WebVTT cue settings are kept in sentence metadata
+    /// under [`CUE_ID`] and [`CUE_SETTINGS`], and the WebVTT voice (`<v Speaker>`)
+    /// becomes the speaker of the sentence.
+    /// Formatting tags are removed from the text and WebVTT escapes such as
+    /// `&amp;` are decoded.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, Segmentation, SubtitleFormat, Tokenizer};
+    ///
+    /// let srt = "1\n00:00:01,000 --> 00:00:02,500\nHello there.\nHow are\n\n\
+    ///            2\n00:00:03,000 --> 00:00:04,000\n<i>you</i> today?\n";
+    ///
+    /// let mut corp = Corpus::new();
+    /// let tokenizer = Tokenizer::new("en");
+    /// let id = corp
+    ///     .import_subtitles(srt.as_bytes(), SubtitleFormat::Srt, Segmentation::Sentence, &tokenizer)
+    ///     .unwrap();
+    ///
+    /// let sentences = corp.doc(id).unwrap().sentences();
+    /// assert_eq!(sentences[1].to_text(), "How are you today?");
+    /// assert_eq!((sentences[1].start(), sentences[1].end()), (Some(1000), Some(4000)));
+    ///
+    /// let malformed = "1\n00:00:01,5 --> 00:00:02,500\nHello.\n";
+    /// assert!(corp
+    ///     .import_subtitles(malformed.as_bytes(), SubtitleFormat::Srt, Segmentation::Cue, &tokenizer)
+    ///     .is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// This will return an error if:
+    /// - Reading from the source fails
+    /// - A timestamp is malformed
+    /// - The file contains no text
+    pub fn import_subtitles<R: BufRead>(
+        &mut self,
+        mut src: R,
+        format: SubtitleFormat,
+        segmentation: Segmentation,
+        tokenizer: &Tokenizer,
+    ) -> Result<u32, CorporeumError> {
+        let mut content = String::new();
+        src.read_to_string(&mut content)?;
+
+        let cues = parse_cues(&content, format)?;
+
+        let mut doc = self.create_doc()?;
+        match segmentation {
+            Segmentation::Cue => add_cues(&mut doc, &cues, tokenizer)?,
+            Segmentation::Sentence => add_resegmented(&mut doc, &cues, tokenizer)?,
+        }
+
+        let id = doc.doc_id();
+        self.add_doc(doc)?;
+        Ok(id)
+    }
+
+    /// Writes a document of the corpus as an SRT or WebVTT file.
+    ///
+    /// Every sentence becomes one cue. WebVTT cues keep their identifier,
+    /// settings and the speaker as a voice span, `&` and `<` in their text are
+    /// escaped.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, Segmentation, SubtitleFormat, Tokenizer, CUE_ID};
+    ///
+    /// let vtt = "WEBVTT\n\nintro\n00:01.000 --> 00:02.000 align:start\n<v Anna>Hi, Bob!\n\n\n\
+    ///            00:02.000 --> 00:03.000\nTom &amp; Jerry &lt;3\n";
+    ///
+    /// let mut corp = Corpus::new();
+    /// let tokenizer = Tokenizer::new("en");
+    /// let id = corp
+    ///     .import_subtitles(vtt.as_bytes(), SubtitleFormat::WebVtt, Segmentation::Cue, &tokenizer)
+    ///     .unwrap();
+    /// let sentences = corp.doc(id).unwrap().sentences();
+    /// assert_eq!(sentences[1].to_text(), "Tom & Jerry <3");
+    /// assert_eq!(sentences[1].meta(CUE_ID), None);
+    ///
+    /// let mut out = Vec::new();
+    /// corp.export_subtitles(id, &mut out, SubtitleFormat::Srt).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(out).unwrap(),
+    ///     "1\n00:00:01,000 --> 00:00:02,000\nHi, Bob!\n\n\
+    ///      2\n00:00:02,000 --> 00:00:03,000\nTom & Jerry <3\n\n"
+    /// );
+    ///
+    /// let mut out = Vec::new();
+    /// corp.export_subtitles(id, &mut out, SubtitleFormat::WebVtt).unwrap();
+    /// assert!(String::from_utf8(out).unwrap().ends_with("\nTom &amp; Jerry &lt;3\n\n"));
+    /// ```
+    ///
+    /// # Errors
+    /// This will return an error if:
+    /// - The document does not exist
+    /// - A sentence has no start or end timestamp
+    /// - Writing into the destination fails
+    pub fn export_subtitles<W: Write>(
+        &self,
+        doc_id: u32,
+        mut dest: W,
+        format: SubtitleFormat,
+    ) -> Result<(), CorporeumError> {
+        let doc = self.doc(doc_id).ok_or_else(|| {
+            CorporeumError::ElementNotFound(format!("Document with ID {doc_id} does not exist"))
+        })?;
+
+        if format == SubtitleFormat::WebVtt {
+            writeln!(dest, "WEBVTT\n")?;
+        }
+
+        for (i, sent) in doc.sentences.iter().enumerate() {
+            let (Some(start), Some(end)) = (sent.start, sent.end) else {
+                return Err(CorporeumError::BadValue(format!(
+                    "Sentence with ID {} has no timestamps",
+                    sent.id
+                )));
+            };
+
+            let text = sent.detokenize();
+            match format {
+                SubtitleFormat::Srt => {
+                    writeln!(dest, "{}", i + 1)?;
+                    writeln!(
+                        dest,
+                        "{} --> {}",
+                        format_timestamp(start, ','),
+                        format_timestamp(end, ',')
+                    )?;
+                    writeln!(dest, "{text}\n")?;
+                }
+                SubtitleFormat::WebVtt => {
+                    if let Some(id) = sent.meta(CUE_ID) {
+                        writeln!(dest, "{id}")?;
+                    }
+                    write!(
+                        dest,
+                        "{} --> {}",
+                        format_timestamp(start, '.'),
+                        format_timestamp(end, '.')
+                    )?;
+                    match sent.meta(CUE_SETTINGS) {
+                        Some(settings) => writeln!(dest, " {settings}")?,
+                        None => writeln!(dest)?,
+                    }
+                    let text = escape_vtt(&text);
+                    match sent.speaker() {
+                        Some(speaker) => writeln!(dest, "<v {}>{text}\n", escape_vtt(speaker))?,
+                        None => writeln!(dest, "{text}\n")?,
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn add_cues(doc: &mut Document, cues: &[Cue], tokenizer: &Tokenizer) -> Result<(), CorporeumError> {
+    let mut ids = free_ids(doc.sentences.iter().map(|s| s.id).max());
+    for cue in cues {
+        let text_tokens = tokenizer.tokenize(&cue.text);
+        if text_tokens.is_empty() {
+            continue;
+        }
+
+        let mut sent = Sentence::<Source>::new(next_free(&mut ids, "sentence")?, tokenizer.lang());
+        sent.add_text_tokens(&text_tokens)?;
+        sent.set_timing(cue.start, cue.end);
+        if let Some(id) = &cue.id {
+            sent.set_meta(CUE_ID, id);
+        }
+        if let Some(settings) = &cue.settings {
+            sent.set_meta(CUE_SETTINGS, settings);
+        }
+        if let Some(speaker) = &cue.speaker {
+            sent.set_speaker(speaker);
+        }
+        doc.add_sentence(sent)?;
+    }
+
+    Ok(())
+}
+
+fn add_resegmented(
+    doc: &mut Document,
+    cues: &[Cue],
+    tokenizer: &Tokenizer,
+) -> Result<(), CorporeumError> {
+    // offsets at which each cue starts in the joined text
+    let mut text = String::new();
+    let mut cue_offsets = Vec::with_capacity(cues.len());
+    for cue in cues {
+        cue_offsets.push(text.len());
+        text.push_str(&cue.text);
+        text.push('\n');
+    }
+
+    let cue_of = |tok: &TextToken| {
+        &cues[cue_offsets
+            .partition_point(|&offset| offset <= tok.offset())
+            .saturating_sub(1)]
+    };
+
+    let mut ids = free_ids(doc.sentences.iter().map(|s| s.id).max());
+    for text_tokens in tokenizer.split(&text) {
+        let (Some(first), Some(last)) = (text_tokens.first(), text_tokens.last()) else {
+            continue;
+        };
+
+        let mut sent = Sentence::<Source>::new(next_free(&mut ids, "sentence")?, tokenizer.lang());
+        sent.add_text_tokens(&text_tokens)?;
+        sent.set_timing(cue_of(first).start, cue_of(last).end);
+        doc.add_sentence(sent)?;
+    }
+
+    Ok(())
+}
+
+fn parse_cues(content: &str, format: SubtitleFormat) -> Result<Vec<Cue>, CorporeumError> {
+    let content = content.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+
+    // blocks of lines separated by one or more blank lines
+    let all_lines: Vec<&str> = content.lines().collect();
+    let mut blocks = Vec::new();
+    let mut line_no = 0;
+    while line_no < all_lines.len() {
+        let len = all_lines[line_no..]
+            .iter()
+            .take_while(|l| !l.trim().is_empty())
+            .count();
+        if len > 0 {
+            blocks.push((line_no, &all_lines[line_no..line_no + len]));
+        }
+        line_no += len.max(1);
+    }
+
+    let mut cues = Vec::new();
+    for (block_start, lines) in blocks {
+        let Some(timing_idx) = lines.iter().position(|l| l.contains("-->")) else {
+            // WebVTT header, NOTE, STYLE and REGION blocks, or stray text
+            continue;
+        };
+        if format == SubtitleFormat::WebVtt && lines[0].starts_with("NOTE") {
+            continue;
+        }
+
+        let (start, rest) = lines[timing_idx].split_once("-->").unwrap_or_default();
+        let rest = rest.trim();
+        let (end, settings) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+
+        let line = block_start + timing_idx + 1;
+        let mut text_lines = Vec::new();
+        let mut speaker = None;
+        for text_line in &lines[timing_idx + 1..] {
+            let (clean, voice) = strip_tags(text_line);
+            speaker = speaker.or(voice);
+            text_lines.push(match format {
+                SubtitleFormat::Srt => clean,
+                SubtitleFormat::WebVtt => unescape_vtt(&clean),
+            });
+        }
+
+        cues.push(Cue {
+            id: (timing_idx > 0).then(|| lines[timing_idx - 1].trim().to_owned()),
+            start: parse_timestamp(start.trim(), line)?,
+            end: parse_timestamp(end, line)?,
+            settings: Some(settings.trim())
+                .filter(|s| !s.is_empty())
+                .map(str::to_owned),
+            speaker,
+            text: text_lines.join("\n"),
+        });
+    }
+
+    Ok(cues)
+}
+
+/// Removes `<...>` and `{...}` formatting tags, returning the text and the
+/// speaker of a WebVTT voice tag if present.
+fn strip_tags(line: &str) -> (String, Option<String>) {
+    let mut text = String::with_capacity(line.len());
+    let mut speaker = None;
+    let mut rest = line;
+
+    while let Some(open) = rest.find(['<', '{']) {
+        let close_char = if rest[open..].starts_with('<') {
+            '>'
+        } else {
+            '}'
+        };
+        let Some(close) = rest[open..].find(close_char) else {
+            break;
+        };
+
+        text.push_str(&rest[..open]);
+        let tag = &rest[open + 1..open + close];
+        if let Some(voice) = tag.strip_prefix('v').filter(|v| v.starts_with([' ', '.'])) {
+            // `<v Name>` or `<v.class Name>`
+            let name = voice.split_once(' ').map_or("", |(_, name)| name).trim();
+            if !name.is_empty() {
+                speaker = Some(name.to_owned());
+            }
+        }
+        rest = &rest[open + close + 1..];
+    }
+    text.push_str(rest);
+
+    (text, speaker)
+}
+
+/// Escapes the characters that would start a tag or an escape in WebVTT cue text.
+fn escape_vtt(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;")
+}
+
+/// Replaces the escapes of WebVTT cue text by the characters they stand for.
+fn unescape_vtt(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", "\u{a0}")
+        .replace("&lrm;", "\u{200e}")
+        .replace("&rlm;", "\u{200f}")
+        .replace("&amp;", "&")
+}
+
+/// Parses `hh:mm:ss,mmm` (SRT) or `[hh:]mm:ss.mmm` (WebVTT) into milliseconds.
+fn parse_timestamp(stamp: &str, line: usize) -> Result<u64, CorporeumError> {
+    let invalid = || {
+        CorporeumError::FailedToParseSemantic(Some(line), format!("Invalid timestamp '{stamp}'"))
+    };
+
+    let number = |digits: &str| {
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        digits.parse::<u64>().map_err(|_| invalid())
+    };
+
+    let (clock, millis) = stamp.split_once([',', '.']).ok_or_else(invalid)?;
+    let parts: Vec<&str> = clock.split(':').collect();
+    if millis.len() != 3 || !(2..=3).contains(&parts.len()) {
+        return Err(invalid());
+    }
+
+    let mut seconds = 0u64;
+    for part in parts {
+        let value = number(part)?;
+        seconds = seconds
+            .checked_mul(60)
+            .and_then(|s| s.checked_add(value))
+            .ok_or_else(invalid)?;
+    }
+
+    let millis = number(millis)?;
+    seconds
+        .checked_mul(1000)
+        .and_then(|ms| ms.checked_add(millis))
+        .ok_or_else(invalid)
+}
+
+fn format_timestamp(ms: u64, separator: char) -> String {
+    let (hours, rest) = (ms / 3_600_000, ms % 3_600_000);
+    let (minutes, rest) = (rest / 60_000, rest % 60_000);
+    let (seconds, millis) = (rest / 1000, rest % 1000);
+
+    format!("{hours:02}:{minutes:02}:{seconds:02}{separator}{millis:03}")
+}
@@ -0,0 +1,436 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    integrity::{free_ids, next_free},
+    schema::{Corpus, Document, Sentence, Source, Token},
+    CorporeumError,
+};
+
+const URL_PREFIXES: [&str; 3] = ["https://", "http://", "www."];
+
+/// Characters that are stripped from the end of a URL candidate, as they
+/// usually belong to the surrounding sentence.
+const URL_TRAILING: [char; 12] = ['.', ',', ';', ':', '!', '?', ')', ']', '}', '"', '\'', '»'];
+
+/// Punctuation that ends a sentence.
+const SENTENCE_FINAL: [char; 7] = ['.', '!', '?', '…', '。', '！', '？'];
+
+/// Punctuation that may follow a sentence-final mark and still belongs to
+/// the same sentence, e.g. `He said "Stop!"`.
+const SENTENCE_CLOSING: [char; 9] = ['"', '\'', '”', '’', '»', '«', ')', ']', '}'];
+
+/// Punctuation characters which are grouped into a single token when repeated,
+/// e.g. `...`, `?!` or `--`.
+const REPEATABLE: [char; 4] = ['.', '!', '?', '-'];
+
+const ABBREVIATIONS_EN: &[&str] = &[
+    "Mr.", "Mrs.", "Ms.", "Dr.", "Prof.", "Sr.", "Jr.", "St.", "Mt.", "vs.", "etc.", "e.g.",
+    "i.e.", "cf.", "approx.", "Inc.", "Ltd.", "Co.", "Corp.", "No.", "Fig.", "Jan.", "Feb.",
+    "Mar.", "Apr.", "Jun.", "Jul.", "Aug.", "Sep.", "Sept.", "Oct.", "Nov.", "Dec.", "U.S.",
+    "U.K.", "a.m.", "p.m.",
+];
+
+const ABBREVIATIONS_SK: &[&str] = &[
+    "napr.", "tzv.", "resp.", "atď.", "t.j.", "tj.", "prof.", "doc.", "Ing.", "Mgr.", "Bc.",
+    "MUDr.", "RNDr.", "PhDr.", "JUDr.", "str.", "č.", "ul.", "hod.", "min.", "mil.", "mld.", "sv.",
+    "pod.", "roč.", "cca.",
+];
+
+const ABBREVIATIONS_CS: &[&str] = &[
+    "např.", "tzv.", "resp.", "atd.", "apod.", "tj.", "t.j.", "prof.", "doc.", "Ing.", "Mgr.",
+    "Bc.", "MUDr.", "RNDr.", "PhDr.", "JUDr.", "str.", "č.", "ul.", "hod.", "min.", "mil.", "mld.",
+    "sv.", "roč.", "cca.",
+];
+
+const ABBREVIATIONS_DE: &[&str] = &[
+    "z.B.", "bzw.", "usw.", "d.h.", "u.a.", "Dr.", "Prof.", "Nr.", "ca.", "vgl.", "evtl.", "ggf.",
+    "Str.", "Hr.", "Fr.", "Jh.", "inkl.", "zzgl.",
+];
+
+/// A single token produced by a [`Tokenizer`](Tokenizer).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextToken<'a> {
+    form: &'a str,
+    offset: usize,
+    space_after: bool,
+    paragraph_after: bool,
+}
+
+impl<'a> TextToken<'a> {
+    /// The surface form of this token.
+    pub const fn form(&self) -> &'a str {
+        self.form
+    }
+
+    /// Byte offset of this token in the tokenized text.
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Whether this token was followed by whitespace in the original text.
+    pub const fn space_after(&self) -> bool {
+        self.space_after
+    }
+}
+
+/// A rule-based, Unicode-aware tokenizer and sentence splitter.
+///
+/// Words are sequences of alphanumeric characters (hyphens and apostrophes
+/// between letters are kept inside the word), every other non-whitespace
+/// character is a separate punctuation token. Abbreviations, URLs and numbers
+/// are kept as single tokens.
+///
+/// # Example
+/// ```
+/// use uniform::Tokenizer;
+///
+/// let tokenizer = Tokenizer::new("en");
+/// let forms: Vec<&str> = tokenizer
+///     .tokenize("Dr. Smith paid $3.50, see https://example.com.")
+///     .iter()
+///     .map(|t| t.form())
+///     .collect();
+///
+/// assert_eq!(
+///     forms,
+///     ["Dr.", "Smith", "paid", "$", "3.50", ",", "see", "https://example.com", "."]
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tokenizer {
+    lang: String,
+    abbreviations: BTreeSet<String>,
+    detect_urls: bool,
+    detect_numbers: bool,
+}
+
+impl Tokenizer {
+    /// Creates a new tokenizer for the specified language.
+    ///
+    /// The built-in list of abbreviations is chosen by the primary language
+    /// subtag (`en`, `sk`, `cs` and `de` are available), other languages
+    /// start with no abbreviations.
+    pub fn new(lang: &str) -> Self {
+        let primary = lang.split(['-', '_']).next().unwrap_or_default();
+        let builtin: &[&str] = match primary.to_lowercase().as_str() {
+            "en" => ABBREVIATIONS_EN,
+            "sk" => ABBREVIATIONS_SK,
+            "cs" => ABBREVIATIONS_CS,
+            "de" => ABBREVIATIONS_DE,
+            _ => &[],
+        };
+
+        Self {
+            lang: lang.to_owned(),
+            abbreviations: builtin.iter().map(|&a| a.to_owned()).collect(),
+            detect_urls: true,
+            detect_numbers: true,
+        }
+    }
+
+    /// Returns the language of sentences created by this tokenizer.
+    pub fn lang(&self) -> &str {
+        &self.lang
+    }
+
+    /// Returns the list of abbreviations kept together with their trailing period.
+    pub const fn abbreviations(&self) -> &BTreeSet<String> {
+        &self.abbreviations
+    }
+
+    /// Adds an abbreviation, e.g. `"approx."`.
+    pub fn add_abbreviation(&mut self, abbreviation: &str) {
+        self.abbreviations.insert(abbreviation.to_owned());
+    }
+
+    /// Removes an abbreviation.
+    pub fn remove_abbreviation(&mut self, abbreviation: &str) {
+        self.abbreviations.remove(abbreviation);
+    }
+
+    /// Removes all abbreviations, including the built-in ones.
+    pub fn clear_abbreviations(&mut self) {
+        self.abbreviations.clear();
+    }
+
+    /// Sets whether URLs (starting with `http://`, `https://` or `www.`) are kept as single tokens.
+    pub fn set_detect_urls(&mut self, detect: bool) {
+        self.detect_urls = detect;
+    }
+
+    /// Sets whether numbers with decimal and thousands separators (`3.14`, `1,000`)
+    /// are kept as single tokens.
+    pub fn set_detect_numbers(&mut self, detect: bool) {
+        self.detect_numbers = detect;
+    }
+
+    /// Splits text into tokens.
+    pub fn tokenize<'a>(&self, text: &'a str) -> Vec<TextToken<'a>> {
+        let mut tokens: Vec<TextToken> = Vec::new();
+        let mut pos = 0;
+
+        while pos < text.len() {
+            let rest = &text[pos..];
+            let Some(first) = rest.chars().next() else {
+                break;
+            };
+
+            if first.is_whitespace() {
+                let ws_len = rest
+                    .find(|c: char| !c.is_whitespace())
+                    .unwrap_or(rest.len());
+                if let Some(prev) = tokens.last_mut() {
+                    prev.space_after = true;
+                    prev.paragraph_after |= rest[..ws_len].matches('\n').count() > 1;
+                }
+                pos += ws_len;
+                continue;
+            }
+
+            let len = self.token_len(rest);
+            tokens.push(TextToken {
+                form: &rest[..len],
+                offset: pos,
+                space_after: false,
+                paragraph_after: false,
+            });
+            pos += len;
+        }
+
+        if let Some(last) = tokens.last_mut() {
+            last.space_after = true;
+        }
+
+        tokens
+    }
+
+    /// Splits text into sentences of tokens.
+    ///
+    /// A sentence ends after sentence-final punctuation (optionally followed by
+    /// closing quotes or brackets) when the next token does not start with a
+    /// lowercase letter, and always at a paragraph break (an empty line).
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::Tokenizer;
+    ///
+    /// let tokenizer = Tokenizer::new("en");
+    /// let sentences = tokenizer.split("It costs approx. 5 dollars. Mr. Brown agreed!");
+    ///
+    /// assert_eq!(sentences.len(), 2);
+    /// assert_eq!(sentences[1][0].form(), "Mr.");
+    /// ```
+    pub fn split<'a>(&self, text: &'a str) -> Vec<Vec<TextToken<'a>>> {
+        let mut sentences = Vec::new();
+        let mut current: Vec<TextToken> = Vec::new();
+        let mut seen_final = false;
+
+        let tokens = self.tokenize(text);
+        for (i, tok) in tokens.iter().enumerate() {
+            let is_final = is_made_of(tok.form, &SENTENCE_FINAL);
+            let is_closing = is_made_of(tok.form, &SENTENCE_CLOSING);
+
+            seen_final = is_final || (seen_final && is_closing);
+            current.push(*tok);
+
+            let next = tokens.get(i + 1);
+            let next_continues = next.is_some_and(|n| {
+                is_made_of(n.form, &SENTENCE_CLOSING)
+                    || n.form.chars().next().is_some_and(char::is_lowercase)
+            });
+
+            if tok.paragraph_after || next.is_none() || (seen_final && !next_continues) {
+                sentences.push(std::mem::take(&mut current));
+                seen_final = false;
+            }
+        }
+
+        sentences
+    }
+
+    /// Returns the byte length of the token at the beginning of `text`.
+    fn token_len(&self, text: &str) -> usize {
+        if self.detect_urls {
+            if let Some(len) = url_len(text) {
+                return len;
+            }
+        }
+
+        if let Some(len) = self.abbreviation_len(text) {
+            return len;
+        }
+
+        let mut chars = text.char_indices().peekable();
+        let Some((_, first)) = chars.next() else {
+            return 0;
+        };
+
+        if !first.is_alphanumeric() {
+            let mut end = first.len_utf8();
+            if REPEATABLE.contains(&first) {
+                while let Some(&(i, c)) = chars.peek() {
+                    if !REPEATABLE.contains(&c) || (first == '-') != (c == '-') {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+            }
+            return end;
+        }
+
+        let numeric = self.detect_numbers && first.is_numeric();
+        let mut end = first.len_utf8();
+        while let Some((i, c)) = chars.next() {
+            if c.is_alphanumeric() {
+                end = i + c.len_utf8();
+                continue;
+            }
+
+            let joins = match c {
+                '-' | '\'' | '’' => !numeric,
+                '.' | ',' => numeric,
+                _ => false,
+            };
+            let continues = chars.peek().is_some_and(|&(_, n)| {
+                if numeric {
+                    n.is_numeric()
+                } else {
+                    n.is_alphanumeric()
+                }
+            });
+
+            if !(joins && continues) {
+                break;
+            }
+            end = i + c.len_utf8();
+        }
+
+        end
+    }
+
+    /// Returns the length of the longest abbreviation at the beginning of `text`.
+    fn abbreviation_len(&self, text: &str) -> Option<usize> {
+        self.abbreviations
+            .iter()
+            .filter(|abbr| {
+                text.starts_with(abbr.as_str())
+                    && !text[abbr.len()..]
+                        .chars()
+                        .next()
+                        .is_some_and(char::is_alphanumeric)
+            })
+            .map(String::len)
+            .max()
+    }
+}
+
+fn url_len(text: &str) -> Option<usize> {
+    if !URL_PREFIXES.iter().any(|p| text.starts_with(p)) {
+        return None;
+    }
+
+    let end = text.find(char::is_whitespace).unwrap_or(text.len());
+    let url = text[..end].trim_end_matches(URL_TRAILING);
+
+    Some(url.len())
+}
+
+fn is_made_of(form: &str, chars: &[char]) -> bool {
+    !form.is_empty() && form.chars().all(|c| chars.contains(&c))
+}
+
+impl<T> Sentence<T> {
+    /// Appends tokens produced by a [`Tokenizer`](Tokenizer), marking the ones
+    /// not followed by whitespace with `SpaceAfter=No`.
+    pub(crate) fn add_text_tokens(
+        &mut self,
+        text_tokens: &[TextToken],
+    ) -> Result<(), CorporeumError> {
+        let mut ids = free_ids(self.tokens.iter().map(|t| t.id).max());
+        for text_tok in text_tokens {
+            let mut tok = Token::new(next_free(&mut ids, "token")?, text_tok.form());
+            if !text_tok.space_after() {
+                tok.set_space_after(false);
+            }
+            self.add_token(tok);
+        }
+        Ok(())
+    }
+}
+
+impl Document {
+    /// Tokenizes the text, splits it into sentences and adds them to this `Document`.
+    ///
+    /// Tokens that were not followed by whitespace are marked with `SpaceAfter=No`
+    /// in their `misc` field, so the text can be restored with
+    /// [`Sentence::to_text()`](crate::Sentence::to_text).
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, Tokenizer};
+    ///
+    /// let mut corp = Corpus::new();
+    /// let mut doc = corp.create_doc().unwrap();
+    /// doc.add_text("Hello, world! How are you?", &Tokenizer::new("en"))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(doc.sentences().len(), 2);
+    /// assert_eq!(doc.sentences()[0].to_text(), "Hello, world!");
+    /// ```
+    ///
+    /// # Errors
+    /// This will return an error if the text contains no tokens.
+    pub fn add_text(&mut self, text: &str, tokenizer: &Tokenizer) -> Result<(), CorporeumError> {
+        let sentences = tokenizer.split(text);
+        if sentences.is_empty() {
+            return Err(CorporeumError::EmptyObject(
+                "Text has no tokens in it".to_owned(),
+            ));
+        }
+
+        let mut ids = free_ids(self.sentences.iter().map(|s| s.id).max());
+        for text_tokens in sentences {
+            let mut sent =
+                Sentence::<Source>::new(next_free(&mut ids, "sentence")?, tokenizer.lang());
+            sent.add_text_tokens(&text_tokens)?;
+            self.add_sentence(sent)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Corpus {
+    /// Creates a new document from plain text and adds it to the corpus.
+    /// Returns the ID of the new document.
+    ///
+    /// See [`Document::add_text()`](crate::Document::add_text) for details.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, Tokenizer};
+    ///
+    /// let mut corp = Corpus::new();
+    /// let id = corp
+    ///     .import_text("První věta. Druhá věta.", &Tokenizer::new("cs"))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(corp.doc(id).unwrap().sentences().len(), 2);
+    /// ```
+    ///
+    /// # Errors
+    /// This will return an error if the text contains no tokens.
+    pub fn import_text(
+        &mut self,
+        text: &str,
+        tokenizer: &Tokenizer,
+    ) -> Result<u32, CorporeumError> {
+        let mut doc = self.create_doc()?;
+        doc.add_text(text, tokenizer)?;
+
+        let id = doc.doc_id();
+        self.add_doc(doc)?;
+        Ok(id)
+    }
+}
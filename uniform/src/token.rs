@@ -1,4 +1,75 @@
-use crate::schema::Token;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{schema::Token, CorporeumError};
+
+/// Names one of the annotation fields of a [`Token`](crate::Token).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenField {
+    Form,
+    Lemma,
+    Upos,
+    Xpos,
+    Feats,
+    Head,
+    Deprel,
+    Deps,
+    Misc,
+    Gloss,
+}
+
+impl TokenField {
+    /// All token fields, the CoNLL-U columns first.
+    pub const ALL: [Self; 10] = [
+        Self::Form,
+        Self::Lemma,
+        Self::Upos,
+        Self::Xpos,
+        Self::Feats,
+        Self::Head,
+        Self::Deprel,
+        Self::Deps,
+        Self::Misc,
+        Self::Gloss,
+    ];
+
+    /// Returns the lowercase name of this field, e.g. `"upos"`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Form => "form",
+            Self::Lemma => "lemma",
+            Self::Upos => "upos",
+            Self::Xpos => "xpos",
+            Self::Feats => "feats",
+            Self::Head => "head",
+            Self::Deprel => "deprel",
+            Self::Deps => "deps",
+            Self::Misc => "misc",
+            Self::Gloss => "gloss",
+        }
+    }
+}
+
+impl fmt::Display for TokenField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for TokenField {
+    type Err = CorporeumError;
+
+    /// Parses a field name case-insensitively, e.g. `"lemma"` or `"UPOS"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|field| field.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| CorporeumError::ElementNotFound(format!("Unknown token field '{s}'")))
+    }
+}
 
 impl Token {
     pub(crate) fn new(id: u32, token: &str) -> Self {
@@ -13,6 +84,60 @@ impl Token {
             deprel: None,
             deps: None,
             misc: None,
+            gloss: None,
+            start: None,
+            end: None,
+        }
+    }
+
+    /// Returns the value of the specified field, `None` if it is not set.
+    pub fn get(&self, field: TokenField) -> Option<&str> {
+        match field {
+            TokenField::Form => Some(&self.form),
+            TokenField::Lemma => self.lemma.as_deref(),
+            TokenField::Upos => self.upos.as_deref(),
+            TokenField::Xpos => self.xpos.as_deref(),
+            TokenField::Feats => self.feats.as_deref(),
+            TokenField::Head => self.head.as_deref(),
+            TokenField::Deprel => self.deprel.as_deref(),
+            TokenField::Deps => self.deps.as_deref(),
+            TokenField::Misc => self.misc.as_deref(),
+            TokenField::Gloss => self.gloss.as_deref(),
+        }
+    }
+
+    /// Sets the value of the specified field.
+    pub fn set(&mut self, field: TokenField, value: &str) {
+        let value = value.to_owned();
+        match field {
+            TokenField::Form => self.form = value,
+            TokenField::Lemma => self.lemma = Some(value),
+            TokenField::Upos => self.upos = Some(value),
+            TokenField::Xpos => self.xpos = Some(value),
+            TokenField::Feats => self.feats = Some(value),
+            TokenField::Head => self.head = Some(value),
+            TokenField::Deprel => self.deprel = Some(value),
+            TokenField::Deps => self.deps = Some(value),
+            TokenField::Misc => self.misc = Some(value),
+            TokenField::Gloss => self.gloss = Some(value),
+        }
+    }
+
+    /// Removes the value of the specified field.
+    ///
+    /// The form of a token cannot be removed, [`TokenField::Form`] is ignored.
+    pub fn remove(&mut self, field: TokenField) {
+        match field {
+            TokenField::Form => {}
+            TokenField::Lemma => self.lemma = None,
+            TokenField::Upos => self.upos = None,
+            TokenField::Xpos => self.xpos = None,
+            TokenField::Feats => self.feats = None,
+            TokenField::Head => self.head = None,
+            TokenField::Deprel => self.deprel = None,
+            TokenField::Deps => self.deps = None,
+            TokenField::Misc => self.misc = None,
+            TokenField::Gloss => self.gloss = None,
         }
     }
 
@@ -80,6 +205,38 @@ impl Token {
         self.head.as_deref()
     }
 
+    /// Returns the ID of the head token, `None` for the root or if the head is
+    /// not set or not a number.
+    ///
+    /// Heads follow CoNLL-U numbering: head `0` is the root and head `n` refers
+    /// to the token with ID `n - 1`.
+    pub fn head_id(&self) -> Option<u32> {
+        self.head.as_deref()?.parse::<u32>().ok()?.checked_sub(1)
+    }
+
+    /// Rewrites the head and the enhanced dependencies after token IDs of the
+    /// sentence changed. `map` returns the new ID of a token, `None` if it was
+    /// removed, in which case the head and the dependencies on it are dropped.
+    pub(crate) fn remap_heads<F: Fn(u32) -> Option<u32>>(&mut self, map: F) {
+        // the root and empty nodes such as 8.1 have no token ID
+        let remap = |head: &str| match head.parse::<u32>() {
+            Ok(n) if n > 0 => map(n - 1).map(|id| (id + 1).to_string()),
+            _ => Some(head.to_owned()),
+        };
+
+        self.head = self.head.as_deref().and_then(remap);
+        if let Some(deps) = self.deps.as_deref() {
+            let deps: Vec<String> = deps
+                .split('|')
+                .filter_map(|dep| match dep.split_once(':') {
+                    Some((head, rel)) => remap(head).map(|head| format!("{head}:{rel}")),
+                    None => Some(dep.to_owned()),
+                })
+                .collect();
+            self.deps = (!deps.is_empty()).then(|| deps.join("|"));
+        }
+    }
+
     pub fn set_head(&mut self, head: &str) {
         self.head = Some(head.to_string());
     }
@@ -123,4 +280,100 @@ impl Token {
     pub fn remove_misc(&mut self) {
         self.misc = None;
     }
+
+    /// Returns the interlinear gloss of this token, e.g. `"they-OBL-GEN"`.
+    ///
+    /// Morphemes are separated the same way as in the segmented form, see
+    /// [`Sentence::to_igt()`](crate::Sentence::to_igt).
+    pub fn gloss(&self) -> Option<&str> {
+        self.gloss.as_deref()
+    }
+
+    pub fn set_gloss(&mut self, gloss: &str) {
+        self.gloss = Some(gloss.to_string());
+    }
+
+    pub fn remove_gloss(&mut self) {
+        self.gloss = None;
+    }
+
+    /// Returns the value of a `Key=Value` entry in the `misc` field.
+    ///
+    /// Entries are separated by `|`, as in CoNLL-U.
+    pub fn misc_value(&self, key: &str) -> Option<&str> {
+        self.misc
+            .as_deref()?
+            .split('|')
+            .find_map(|entry| entry.strip_prefix(key)?.strip_prefix('='))
+    }
+
+    /// Sets a `Key=Value` entry in the `misc` field, replacing the previous value of `key`.
+    pub fn set_misc_value(&mut self, key: &str, value: &str) {
+        self.remove_misc_value(key);
+
+        let entry = format!("{key}={value}");
+        match self.misc.as_mut() {
+            Some(misc) => {
+                misc.push('|');
+                misc.push_str(&entry);
+            }
+            None => self.misc = Some(entry),
+        }
+    }
+
+    /// Removes a `Key=Value` entry from the `misc` field.
+    pub fn remove_misc_value(&mut self, key: &str) {
+        let Some(misc) = self.misc.as_deref() else {
+            return;
+        };
+
+        let kept = misc
+            .split('|')
+            .filter(|entry| {
+                entry
+                    .strip_prefix(key)
+                    .is_none_or(|rest| !rest.starts_with('='))
+            })
+            .collect::<Vec<&str>>()
+            .join("|");
+
+        self.misc = if kept.is_empty() { None } else { Some(kept) };
+    }
+
+    /// Returns the start offset of this token in milliseconds.
+    pub const fn start(&self) -> Option<u64> {
+        self.start
+    }
+
+    /// Returns the end offset of this token in milliseconds.
+    pub const fn end(&self) -> Option<u64> {
+        self.end
+    }
+
+    /// Sets the start and end offsets of this token in milliseconds.
+    pub fn set_timing(&mut self, start: u64, end: u64) {
+        self.start = Some(start);
+        self.end = Some(end);
+    }
+
+    /// Removes the start and end offsets of this token.
+    pub fn remove_timing(&mut self) {
+        self.start = None;
+        self.end = None;
+    }
+
+    /// Returns `false` if this token is marked with `SpaceAfter=No`.
+    pub fn space_after(&self) -> bool {
+        self.misc_value("SpaceAfter") != Some("No")
+    }
+
+    /// Marks whether this token is followed by a space, using the CoNLL-U
+    /// `SpaceAfter=No` convention in the `misc` field.
+    pub fn set_space_after(&mut self, space_after: bool) {
+        if space_after {
+            self.remove_misc_value("SpaceAfter");
+        } else {
+            self.set_misc_value("SpaceAfter", "No");
+        }
+    }
 }
@@ -0,0 +1,397 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    query::{Condition, Parser},
+    schema::{Corpus, Sentence, Token},
+    CorporeumError,
+};
+
+/// A dependency tree pattern in a Grew-like syntax.
+///
+/// A pattern consists of a `pattern { ... }` clause followed by any number of
+/// `without { ... }` clauses. Each clause is a list of statements separated by `;`:
+///
+/// - `V [upos="VERB"]` declares the node `V` with a token condition in the
+///   syntax of [`Query`](crate::Query), e.g. `[lemma="go" & !feats=".*Tense=Past.*"]`.
+/// - `V -[nsubj]-> S` requires `S` to depend on `V` with the relation `nsubj`;
+///   `-[nsubj|obj]->` accepts any of the relations, `-[^punct]->` any relation
+///   but the listed ones and `V -> S` any relation at all.
+/// - `A < B` requires `A` to immediately precede `B`, `A << B` to precede it.
+///
+/// Nodes used in relations need not be declared, they then match any token.
+/// Different variables are always bound to different tokens. A match of the
+/// `pattern` clause is rejected if any `without` clause can be satisfied by
+/// extending it; `without` clauses may refer to nodes of the `pattern` clause
+/// and put further conditions on them.
+///
+/// Relations are read from [`head`](crate::Token::head) and
+/// [`deprel`](crate::Token::deprel), see [`Token::head_id()`](crate::Token::head_id).
+///
+/// # Example
+/// ```
+/// use uniform::{Corpus, TreePattern};
+///
+/// let mut corp = Corpus::new();
+/// let mut doc = corp.create_doc().unwrap();
+/// let mut sent = doc.create_sentence("en").unwrap();
+/// for (form, upos, head, deprel) in [
+///     ("Mary", "PROPN", "2", "nsubj"),
+///     ("saw", "VERB", "0", "root"),
+///     ("him", "PRON", "2", "obj"),
+/// ] {
+///     let mut tok = sent.create_token(form).unwrap();
+///     tok.set_lemma(&form.to_lowercase());
+///     tok.set_upos(upos);
+///     tok.set_head(head);
+///     tok.set_deprel(deprel);
+///     sent.add_token(tok);
+/// }
+/// doc.add_sentence(sent).unwrap();
+/// corp.add_doc(doc).unwrap();
+///
+/// let pattern = TreePattern::parse(
+///     r#"pattern { V [upos="VERB"]; S [lemma="mary"]; O [upos="PRON"]; V -[nsubj]-> S; V -[obj]-> O }"#,
+/// )
+/// .unwrap();
+/// let matches = corp.tree_query(&pattern);
+/// assert_eq!(matches.len(), 1);
+/// assert_eq!(matches[0].node("V"), Some(1));
+/// assert_eq!(matches[0].node("O"), Some(2));
+///
+/// let pattern = TreePattern::parse(
+///     r#"pattern { V [upos="VERB"] } without { V -[obj]-> O; O [upos="PRON"] }"#,
+/// )
+/// .unwrap();
+/// assert!(corp.tree_query(&pattern).is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TreePattern {
+    vars: Vec<String>,
+    pattern: Clause,
+    without: Vec<Clause>,
+}
+
+/// A match of a [`TreePattern`]: the token IDs bound to the pattern variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeMatch {
+    doc_id: u32,
+    sentence_id: u32,
+    nodes: BTreeMap<String, u32>,
+}
+
+impl TreeMatch {
+    pub const fn doc_id(&self) -> u32 {
+        self.doc_id
+    }
+
+    pub const fn sentence_id(&self) -> u32 {
+        self.sentence_id
+    }
+
+    /// Returns the token IDs of all variables of the `pattern` clause.
+    pub const fn nodes(&self) -> &BTreeMap<String, u32> {
+        &self.nodes
+    }
+
+    /// Returns the token ID bound to the variable.
+    pub fn node(&self, var: &str) -> Option<u32> {
+        self.nodes.get(var).copied()
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Relation {
+    Any,
+    Labels(Vec<String>, bool),
+}
+
+impl Relation {
+    fn matches(&self, deprel: Option<&str>) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Labels(labels, negated) => {
+                labels.iter().any(|l| Some(l.as_str()) == deprel) != *negated
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Constraint {
+    Edge(usize, Relation, usize),
+    Precedes(usize, usize, bool),
+}
+
+impl Constraint {
+    const fn last_var(&self) -> usize {
+        match self {
+            Self::Edge(a, _, b) | Self::Precedes(a, b, _) => {
+                if *a > *b {
+                    *a
+                } else {
+                    *b
+                }
+            }
+        }
+    }
+
+    fn holds(&self, binding: &[usize], ctx: &Context) -> bool {
+        match self {
+            Self::Edge(gov, rel, dep) => {
+                let (gov, dep) = (binding[*gov], binding[*dep]);
+                ctx.parents[dep] == Some(gov) && rel.matches(ctx.tokens[dep].deprel())
+            }
+            Self::Precedes(a, b, immediately) => {
+                let (a, b) = (binding[*a], binding[*b]);
+                if *immediately {
+                    a + 1 == b
+                } else {
+                    a < b
+                }
+            }
+        }
+    }
+}
+
+/// One `pattern` or `without` clause. Variables are numbered across the whole
+/// pattern, the clause introduces the variables `offset..offset + nodes.len()`.
+#[derive(Debug, Clone)]
+struct Clause {
+    offset: usize,
+    nodes: Vec<Condition>,
+    checks: Vec<(usize, Condition)>,
+    constraints: Vec<Constraint>,
+}
+
+/// Tokens of a sentence with their heads resolved to token indices.
+pub(crate) struct Context<'a> {
+    pub(crate) tokens: &'a [Token],
+    /// index of the head of every token
+    pub(crate) parents: Vec<Option<usize>>,
+}
+
+impl<'a> Context<'a> {
+    pub(crate) fn new<T>(sent: &'a Sentence<T>) -> Self {
+        let index: HashMap<u32, usize> = sent
+            .tokens
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.id, i))
+            .collect();
+
+        Self {
+            tokens: &sent.tokens,
+            parents: sent
+                .tokens
+                .iter()
+                .map(|t| t.head_id().and_then(|id| index.get(&id).copied()))
+                .collect(),
+        }
+    }
+}
+
+impl Clause {
+    /// Binds the variables of this clause in all possible ways, calling `found`
+    /// with every complete binding until it returns `false`.
+    fn search(
+        &self,
+        ctx: &Context,
+        binding: &mut Vec<usize>,
+        found: &mut dyn FnMut(&[usize]) -> bool,
+    ) -> bool {
+        let k = binding.len() - self.offset;
+        // conditions on variables bound by the outer clause
+        if k == 0
+            && !(self
+                .checks
+                .iter()
+                .all(|(var, cond)| cond.matches(&ctx.tokens[binding[*var]]))
+                && self
+                    .constraints
+                    .iter()
+                    .filter(|c| c.last_var() < self.offset)
+                    .all(|c| c.holds(binding, ctx)))
+        {
+            return true;
+        }
+        if k == self.nodes.len() {
+            return found(binding);
+        }
+
+        for idx in 0..ctx.tokens.len() {
+            if binding.contains(&idx) || !self.nodes[k].matches(&ctx.tokens[idx]) {
+                continue;
+            }
+
+            binding.push(idx);
+            let consistent = self
+                .constraints
+                .iter()
+                .filter(|c| c.last_var() == binding.len() - 1)
+                .all(|c| c.holds(binding, ctx));
+            let go_on = !consistent || self.search(ctx, binding, found);
+            binding.pop();
+            if !go_on {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+fn parse_clause(
+    parser: &mut Parser,
+    keyword: &str,
+    vars: &mut Vec<String>,
+) -> Result<Clause, CorporeumError> {
+    parser.expect(keyword)?;
+    parser.expect("{")?;
+
+    let mut clause = Clause {
+        offset: vars.len(),
+        nodes: Vec::new(),
+        checks: Vec::new(),
+        constraints: Vec::new(),
+    };
+
+    let mut var = |parser: &mut Parser, clause: &mut Clause| -> Result<usize, CorporeumError> {
+        let name = parser.var()?;
+        Ok(vars.iter().position(|v| v == name).unwrap_or_else(|| {
+            vars.push(name.to_owned());
+            clause.nodes.push(Condition::Any);
+            vars.len() - 1
+        }))
+    };
+
+    while !parser.eat("}") {
+        if parser.at_end() {
+            return Err(parser.error("Expected '}'"));
+        }
+        if parser.eat(";") {
+            continue;
+        }
+
+        let a = var(parser, &mut clause)?;
+        if parser.peek() == Some('[') {
+            let cond = parser.token_condition()?;
+            if a < clause.offset {
+                clause.checks.push((a, cond));
+            } else {
+                let node = &mut clause.nodes[a - clause.offset];
+                *node = match std::mem::replace(node, Condition::Any) {
+                    Condition::Any => cond,
+                    prev => Condition::And(vec![prev, cond]),
+                };
+            }
+        } else if parser.eat("->") {
+            let b = var(parser, &mut clause)?;
+            clause
+                .constraints
+                .push(Constraint::Edge(a, Relation::Any, b));
+        } else if parser.eat("-[") {
+            let label = parser.until("]->")?.trim();
+            let (label, negated) = label
+                .strip_prefix('^')
+                .map_or((label, false), |label| (label, true));
+            let labels: Vec<String> = label.split('|').map(|l| l.trim().to_owned()).collect();
+            if labels.iter().any(String::is_empty) {
+                return Err(parser.error("Empty relation label"));
+            }
+            let b = var(parser, &mut clause)?;
+            clause
+                .constraints
+                .push(Constraint::Edge(a, Relation::Labels(labels, negated), b));
+        } else if parser.eat("<<") {
+            let b = var(parser, &mut clause)?;
+            clause.constraints.push(Constraint::Precedes(a, b, false));
+        } else if parser.eat("<") {
+            let b = var(parser, &mut clause)?;
+            clause.constraints.push(Constraint::Precedes(a, b, true));
+        } else {
+            return Err(parser.error("Expected a node condition or a relation"));
+        }
+    }
+
+    Ok(clause)
+}
+
+impl TreePattern {
+    /// Parses a tree pattern, see [`TreePattern`] for the syntax.
+    ///
+    /// # Errors
+    /// This will return [`CorporeumError::InvalidQuery`] with the offset of the
+    /// problem if the pattern is malformed or declares no node.
+    pub fn parse(pattern: &str) -> Result<Self, CorporeumError> {
+        let mut parser = Parser::new(pattern);
+        let mut vars = Vec::new();
+
+        let clause = parse_clause(&mut parser, "pattern", &mut vars)?;
+        if clause.nodes.is_empty() {
+            return Err(parser.error("The pattern declares no node"));
+        }
+        let pattern_vars = vars.len();
+
+        let mut without = Vec::new();
+        while !parser.at_end() {
+            without.push(parse_clause(&mut parser, "without", &mut vars)?);
+            // variables of a `without` clause are local to it
+            vars.truncate(pattern_vars);
+        }
+
+        Ok(Self {
+            vars,
+            pattern: clause,
+            without,
+        })
+    }
+
+    /// Finds all matches of this pattern in a sentence, as maps from variable
+    /// names to token IDs.
+    pub fn find_in<T>(&self, sent: &Sentence<T>) -> Vec<BTreeMap<String, u32>> {
+        let ctx = Context::new(sent);
+        let mut matches = Vec::new();
+
+        self.pattern.search(&ctx, &mut Vec::new(), &mut |binding| {
+            let excluded = self.without.iter().any(|clause| {
+                let mut satisfied = false;
+                clause.search(&ctx, &mut binding.to_vec(), &mut |_| {
+                    satisfied = true;
+                    false
+                });
+                satisfied
+            });
+
+            if !excluded {
+                matches.push(
+                    self.vars
+                        .iter()
+                        .zip(binding)
+                        .map(|(var, &idx)| (var.clone(), ctx.tokens[idx].id))
+                        .collect(),
+                );
+            }
+            true
+        });
+
+        matches
+    }
+}
+
+impl Corpus {
+    /// Finds all matches of a tree pattern in the source sentences of the corpus,
+    /// see [`TreePattern::find_in()`].
+    pub fn tree_query(&self, pattern: &TreePattern) -> Vec<TreeMatch> {
+        let mut matches = Vec::new();
+        for doc in &self.documents {
+            for sent in &doc.sentences {
+                matches.extend(pattern.find_in(sent).into_iter().map(|nodes| TreeMatch {
+                    doc_id: doc.id,
+                    sentence_id: sent.id,
+                    nodes,
+                }));
+            }
+        }
+        matches
+    }
+}
@@ -0,0 +1,666 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::schema::{Corpus, Sentence, Source, Token};
+
+/// Sentence metadata key holding the original text of a sentence (`# text` in CoNLL-U).
+pub const SENTENCE_TEXT: &str = "text";
+
+const UPOS: [&str; 17] = [
+    "ADJ", "ADP", "ADV", "AUX", "CCONJ", "DET", "INTJ", "NOUN", "NUM", "PART", "PRON", "PROPN",
+    "PUNCT", "SCONJ", "SYM", "VERB", "X",
+];
+
+const DEPRELS: [&str; 37] = [
+    "acl",
+    "advcl",
+    "advmod",
+    "amod",
+    "appos",
+    "aux",
+    "case",
+    "cc",
+    "ccomp",
+    "clf",
+    "compound",
+    "conj",
+    "cop",
+    "csubj",
+    "dep",
+    "det",
+    "discourse",
+    "dislocated",
+    "expl",
+    "fixed",
+    "flat",
+    "goeswith",
+    "iobj",
+    "list",
+    "mark",
+    "nmod",
+    "nsubj",
+    "nummod",
+    "obj",
+    "obl",
+    "orphan",
+    "parataxis",
+    "punct",
+    "reparandum",
+    "root",
+    "vocative",
+    "xcomp",
+];
+
+/// Universal features with their universal values. An empty list accepts any value.
+const FEATURES: [(&str, &[&str]); 30] = [
+    (
+        "PronType",
+        &[
+            "Art", "Dem", "Emp", "Exc", "Ind", "Int", "Neg", "Prs", "Rcp", "Rel", "Tot",
+        ],
+    ),
+    (
+        "NumType",
+        &["Card", "Dist", "Frac", "Mult", "Ord", "Range", "Sets"],
+    ),
+    ("Poss", &["Yes"]),
+    ("Reflex", &["Yes"]),
+    ("Foreign", &["Yes"]),
+    ("Abbr", &["Yes"]),
+    ("Typo", &["Yes"]),
+    (
+        "ExtPos",
+        &[
+            "ADJ", "ADP", "ADV", "AUX", "CCONJ", "DET", "INTJ", "PRON", "PROPN", "SCONJ",
+        ],
+    ),
+    ("Gender", &["Com", "Fem", "Masc", "Neut"]),
+    ("Animacy", &["Anim", "Hum", "Inan", "Nhum"]),
+    ("NounClass", &[]),
+    (
+        "Number",
+        &[
+            "Coll", "Count", "Dual", "Grpa", "Grpl", "Inv", "Pauc", "Plur", "Ptan", "Sing", "Tri",
+        ],
+    ),
+    (
+        "Case",
+        &[
+            "Abs", "Acc", "Erg", "Nom", "Abe", "Ben", "Cau", "Cmp", "Cns", "Com", "Dat", "Dis",
+            "Equ", "Gen", "Ins", "Par", "Tem", "Tra", "Voc", "Abl", "Add", "Ade", "All", "Del",
+            "Ela", "Ess", "Ill", "Ine", "Lat", "Loc", "Per", "Sbe", "Sbl", "Spl", "Sub", "Sup",
+            "Ter",
+        ],
+    ),
+    ("Definite", &["Com", "Cons", "Def", "Ind", "Spec"]),
+    (
+        "Deixis",
+        &["Abv", "Bel", "Even", "Med", "Nvis", "Prox", "Remt"],
+    ),
+    ("DeixisRef", &["1", "2"]),
+    ("Degree", &["Abs", "Aug", "Cmp", "Dim", "Equ", "Pos", "Sup"]),
+    (
+        "VerbForm",
+        &["Conv", "Fin", "Gdv", "Ger", "Inf", "Part", "Sup", "Vnoun"],
+    ),
+    (
+        "Mood",
+        &[
+            "Adm", "Cnd", "Des", "Imp", "Ind", "Int", "Irr", "Jus", "Nec", "Opt", "Pot", "Prp",
+            "Qot", "Sub",
+        ],
+    ),
+    ("Tense", &["Fut", "Imp", "Past", "Pqp", "Pres"]),
+    ("Aspect", &["Hab", "Imp", "Iter", "Perf", "Prog", "Prosp"]),
+    (
+        "Voice",
+        &[
+            "Act", "Antip", "Bfoc", "Cau", "Dir", "Inv", "Lfoc", "Mid", "Pass", "Rcp",
+        ],
+    ),
+    ("Evident", &["Fh", "Nfh"]),
+    ("Polarity", &["Neg", "Pos"]),
+    ("Person", &["0", "1", "2", "3", "4"]),
+    ("Polite", &["Elev", "Form", "Humb", "Infm"]),
+    ("Clusivity", &["Ex", "In"]),
+    ("NumForm", &["Combi", "Digit", "Roman", "Word"]),
+    ("NumValue", &["1", "2", "3"]),
+    ("Style", &[]),
+];
+
+/// Severity of a [`Diagnostic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The data is probably fine but unusual, e.g. a language-specific feature.
+    Warning,
+    /// The data violates a rule.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+/// Where a [`Diagnostic`] was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct Location {
+    doc_id: Option<u32>,
+    sentence_id: Option<u32>,
+    token_id: Option<u32>,
+}
+
+impl Location {
+    pub(crate) const fn corpus() -> Self {
+        Self {
+            doc_id: None,
+            sentence_id: None,
+            token_id: None,
+        }
+    }
+
+    pub(crate) const fn doc(doc_id: u32) -> Self {
+        Self {
+            doc_id: Some(doc_id),
+            sentence_id: None,
+            token_id: None,
+        }
+    }
+
+    pub(crate) const fn sentence(doc_id: u32, sentence_id: u32) -> Self {
+        Self {
+            doc_id: Some(doc_id),
+            sentence_id: Some(sentence_id),
+            token_id: None,
+        }
+    }
+
+    pub(crate) const fn token(doc_id: u32, sentence_id: u32, token_id: u32) -> Self {
+        Self {
+            doc_id: Some(doc_id),
+            sentence_id: Some(sentence_id),
+            token_id: Some(token_id),
+        }
+    }
+
+    /// Returns the document ID, `None` for problems of the whole corpus.
+    pub const fn doc_id(&self) -> Option<u32> {
+        self.doc_id
+    }
+
+    pub const fn sentence_id(&self) -> Option<u32> {
+        self.sentence_id
+    }
+
+    pub const fn token_id(&self) -> Option<u32> {
+        self.token_id
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(doc_id) = self.doc_id else {
+            return f.write_str("corpus");
+        };
+        write!(f, "doc {doc_id}")?;
+        if let Some(sentence_id) = self.sentence_id {
+            write!(f, ", sentence {sentence_id}")?;
+        }
+        if let Some(token_id) = self.token_id {
+            write!(f, ", token {token_id}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A problem found by a check, e.g. [`Corpus::validate()`] or
+/// [`Corpus::check_integrity()`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    severity: Severity,
+    location: Location,
+    code: &'static str,
+    message: String,
+}
+
+impl Diagnostic {
+    pub(crate) const fn new(
+        severity: Severity,
+        location: Location,
+        code: &'static str,
+        message: String,
+    ) -> Self {
+        Self {
+            severity,
+            location,
+            code,
+            message,
+        }
+    }
+
+    pub const fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub const fn location(&self) -> Location {
+        self.location
+    }
+
+    /// Returns a short machine-readable identifier of the check, e.g. `"invalid-upos"`.
+    pub const fn code(&self) -> &'static str {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} [{}]: {}",
+            self.location, self.severity, self.code, self.message
+        )
+    }
+}
+
+/// Collects the diagnostics of one sentence.
+struct Checker<'a> {
+    doc_id: u32,
+    sent: &'a Sentence<Source>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+    fn report(
+        &mut self,
+        severity: Severity,
+        tok: Option<&Token>,
+        code: &'static str,
+        message: String,
+    ) {
+        let location = match tok {
+            Some(tok) => Location::token(self.doc_id, self.sent.id, tok.id),
+            None => Location::sentence(self.doc_id, self.sent.id),
+        };
+        self.diagnostics
+            .push(Diagnostic::new(severity, location, code, message));
+    }
+
+    fn check_upos(&mut self, tok: &Token) {
+        if let Some(upos) = tok.upos() {
+            if !UPOS.contains(&upos) {
+                self.report(
+                    Severity::Error,
+                    Some(tok),
+                    "invalid-upos",
+                    format!("Unknown UPOS tag '{upos}'"),
+                );
+            }
+        }
+    }
+
+    fn check_feats(&mut self, tok: &Token) {
+        let Some(feats) = tok.feats() else {
+            return;
+        };
+
+        let mut prev: Option<&str> = None;
+        for feat in feats.split('|') {
+            let Some((name, values)) = feat.split_once('=') else {
+                self.report(
+                    Severity::Error,
+                    Some(tok),
+                    "invalid-feats",
+                    format!("Feature '{feat}' is not of the form Name=Value"),
+                );
+                continue;
+            };
+
+            let valid_name = name.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '[' | ']'));
+            let valid_values = values.split(',').all(|v| {
+                v.chars()
+                    .next()
+                    .is_some_and(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+                    && v.chars().all(|c| c.is_ascii_alphanumeric())
+            });
+            if !valid_name || !valid_values {
+                self.report(
+                    Severity::Error,
+                    Some(tok),
+                    "invalid-feats",
+                    format!("Malformed feature '{feat}'"),
+                );
+                continue;
+            }
+
+            if prev.is_some_and(|p| p.to_lowercase() >= name.to_lowercase()) {
+                self.report(
+                    Severity::Error,
+                    Some(tok),
+                    "unsorted-feats",
+                    format!("Feature '{name}' is out of order or repeated"),
+                );
+            }
+            prev = Some(name);
+
+            // layered features such as Number[psor] share the inventory of Number
+            let base = name.split('[').next().unwrap_or(name);
+            match FEATURES.iter().find(|(f, _)| *f == base) {
+                None => self.report(
+                    Severity::Warning,
+                    Some(tok),
+                    "unknown-feature",
+                    format!("'{name}' is not a universal feature"),
+                ),
+                Some((_, known)) => {
+                    for value in values.split(',') {
+                        if !known.is_empty() && !known.contains(&value) {
+                            self.report(
+                                Severity::Warning,
+                                Some(tok),
+                                "unknown-feature-value",
+                                format!("'{value}' is not a universal value of {name}"),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_deprel(&mut self, tok: &Token) {
+        if let Some(deprel) = tok.deprel() {
+            let base = deprel.split(':').next().unwrap_or(deprel);
+            if !DEPRELS.contains(&base) {
+                self.report(
+                    Severity::Error,
+                    Some(tok),
+                    "invalid-deprel",
+                    format!("Unknown dependency relation '{deprel}'"),
+                );
+            }
+        }
+    }
+
+    /// Multiword range lines (`1-2`) and empty nodes (`8.1`) have no ID in
+    /// uniform; once imported they look like tokens without annotation,
+    /// which also shifts the numbering of heads.
+    fn check_multiword(&mut self) {
+        let tokens = &self.sent.tokens;
+        let annotated = tokens.iter().filter(|t| t.upos().is_some()).count();
+        if annotated == 0 || annotated == tokens.len() {
+            return;
+        }
+
+        for tok in tokens {
+            if tok.upos().is_some() || tok.head().is_some() || tok.deprel().is_some() {
+                continue;
+            }
+
+            let message = format!(
+                "Token '{}' has no annotation and may be a multiword token range; \
+                 uniform does not model ranges, head numbers after it may be off",
+                tok.form()
+            );
+            self.report(Severity::Warning, Some(tok), "multiword-token", message);
+        }
+    }
+
+    fn check_text(&mut self) {
+        if let Some(text) = self.sent.meta(SENTENCE_TEXT) {
+            let tokens = self.sent.to_text();
+            if text != tokens {
+                self.report(
+                    Severity::Error,
+                    None,
+                    "text-mismatch",
+                    format!("Sentence text '{text}' does not match the tokens '{tokens}'"),
+                );
+            }
+        }
+    }
+
+    fn check_tree(&mut self) {
+        let tokens = &self.sent.tokens;
+        if tokens.iter().all(|t| t.head().is_none()) {
+            return;
+        }
+
+        let index: HashMap<u32, usize> =
+            tokens.iter().enumerate().map(|(i, t)| (t.id, i)).collect();
+        let mut parents: Vec<Option<usize>> = vec![None; tokens.len()];
+        let mut roots = Vec::new();
+
+        for (i, tok) in tokens.iter().enumerate() {
+            let Some(head) = tok.head() else {
+                // tokens without any annotation are reported by `check_multiword`
+                if tok.upos().is_some() || tok.deprel().is_some() {
+                    self.report(
+                        Severity::Error,
+                        Some(tok),
+                        "missing-head",
+                        "Token has no head".to_owned(),
+                    );
+                }
+                continue;
+            };
+            let Ok(head) = head.parse::<u32>() else {
+                self.report(
+                    Severity::Error,
+                    Some(tok),
+                    "invalid-head",
+                    format!("Head '{head}' is not a number"),
+                );
+                continue;
+            };
+
+            if head == 0 {
+                roots.push(i);
+                if tok.deprel().is_some_and(|d| d != "root") {
+                    self.report(
+                        Severity::Error,
+                        Some(tok),
+                        "root-deprel",
+                        "The root must have the relation 'root'".to_owned(),
+                    );
+                }
+                continue;
+            }
+
+            match tok.head_id().and_then(|id| index.get(&id)) {
+                None => self.report(
+                    Severity::Error,
+                    Some(tok),
+                    "head-out-of-range",
+                    format!("Head {head} does not refer to a token of the sentence"),
+                ),
+                Some(&parent) if parent == i => self.report(
+                    Severity::Error,
+                    Some(tok),
+                    "self-loop",
+                    "Token is its own head".to_owned(),
+                ),
+                Some(&parent) => {
+                    parents[i] = Some(parent);
+                    if tok.deprel() == Some("root") {
+                        self.report(
+                            Severity::Error,
+                            Some(tok),
+                            "root-deprel",
+                            "Only the root may have the relation 'root'".to_owned(),
+                        );
+                    }
+                }
+            }
+        }
+
+        match roots.len() {
+            0 => self.report(
+                Severity::Error,
+                None,
+                "no-root",
+                "Sentence has no root".to_owned(),
+            ),
+            1 => {}
+            n => self.report(
+                Severity::Error,
+                None,
+                "multiple-roots",
+                format!("Sentence has {n} roots"),
+            ),
+        }
+
+        self.check_cycles(&parents);
+        self.check_punct(&parents);
+    }
+
+    fn check_cycles(&mut self, parents: &[Option<usize>]) {
+        // 0 unvisited, 1 on the current path, 2 done
+        let mut state = vec![0u8; parents.len()];
+        for start in 0..parents.len() {
+            let mut path = Vec::new();
+            let mut node = Some(start);
+            while let Some(i) = node {
+                match state[i] {
+                    0 => {
+                        state[i] = 1;
+                        path.push(i);
+                        node = parents[i];
+                    }
+                    1 => {
+                        let tok = &self.sent.tokens[i];
+                        self.report(
+                            Severity::Error,
+                            Some(tok),
+                            "cycle",
+                            "Token is part of a cycle".to_owned(),
+                        );
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+            for i in path {
+                state[i] = 2;
+            }
+        }
+    }
+
+    fn check_punct(&mut self, parents: &[Option<usize>]) {
+        for (i, tok) in self.sent.tokens.iter().enumerate() {
+            let is_punct_rel = tok.deprel() == Some("punct");
+            let is_punct_tag = tok.upos() == Some("PUNCT");
+            if is_punct_rel && tok.upos().is_some() && !is_punct_tag {
+                self.report(
+                    Severity::Error,
+                    Some(tok),
+                    "upos-rel-consistency",
+                    format!(
+                        "Relation 'punct' requires UPOS PUNCT, not {}",
+                        tok.upos().unwrap_or_default()
+                    ),
+                );
+            }
+            if is_punct_tag && tok.deprel().is_some_and(|d| d != "punct" && d != "root") {
+                self.report(
+                    Severity::Warning,
+                    Some(tok),
+                    "upos-rel-consistency",
+                    format!(
+                        "PUNCT is attached as '{}'",
+                        tok.deprel().unwrap_or_default()
+                    ),
+                );
+            }
+            if is_punct_rel && parents.contains(&Some(i)) {
+                self.report(
+                    Severity::Error,
+                    Some(tok),
+                    "punct-is-nonleaf",
+                    "Punctuation must not have dependents".to_owned(),
+                );
+            }
+        }
+    }
+}
+
+impl Corpus {
+    /// Checks the source sentences of the corpus against Universal Dependencies rules.
+    ///
+    /// The checks cover UPOS tags, the format and universal inventory of
+    /// features, universal dependency relations, the tree structure (heads in
+    /// range, exactly one root, no cycles), punctuation attachment and the
+    /// sentence text in the [`SENTENCE_TEXT`] metadata entry. Heads are read as
+    /// described in [`Token::head_id()`](crate::Token::head_id).
+    ///
+    /// uniform has no notion of multiword token ranges or empty nodes. Tokens
+    /// without any annotation in annotated sentences are reported as likely
+    /// range lines, this check is a heuristic.
+    ///
+    /// All problems are collected instead of failing on the first one, errors
+    /// and warnings in corpus order.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, Severity};
+    ///
+    /// let mut corp = Corpus::new();
+    /// let mut doc = corp.create_doc().unwrap();
+    /// let mut sent = doc.create_sentence("en").unwrap();
+    /// for (form, upos, head, deprel) in [("Dogs", "NOUN", "2", "nsubj"), ("bark", "VERB", "0", "root"), ("!", "PUNCT", "1", "punct")] {
+    ///     let mut tok = sent.create_token(form).unwrap();
+    ///     tok.set_upos(upos);
+    ///     tok.set_head(head);
+    ///     tok.set_deprel(deprel);
+    ///     sent.add_token(tok);
+    /// }
+    /// sent.set_meta("text", "Dogs bark!");
+    /// doc.add_sentence(sent).unwrap();
+    /// corp.add_doc(doc).unwrap();
+    ///
+    /// let diagnostics = corp.validate();
+    /// assert_eq!(diagnostics.len(), 1);
+    /// assert_eq!(diagnostics[0].code(), "text-mismatch");
+    /// assert_eq!(diagnostics[0].severity(), Severity::Error);
+    /// assert_eq!(diagnostics[0].location().sentence_id(), Some(0));
+    /// ```
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for doc in &self.documents {
+            for sent in &doc.sentences {
+                let mut checker = Checker {
+                    doc_id: doc.id,
+                    sent,
+                    diagnostics: Vec::new(),
+                };
+
+                for tok in &sent.tokens {
+                    checker.check_upos(tok);
+                    checker.check_feats(tok);
+                    checker.check_deprel(tok);
+                }
+                checker.check_multiword();
+                checker.check_tree();
+                checker.check_text();
+
+                diagnostics.append(&mut checker.diagnostics);
+            }
+        }
+
+        diagnostics
+    }
+}
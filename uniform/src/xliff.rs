@@ -0,0 +1,478 @@
+use quick_xml::events::{BytesDecl, Event};
+use quick_xml::{Reader, Writer};
+use std::io::{BufRead, Write};
+
+use crate::{
+    integrity::{free_ids, next_free},
+    schema::{Corpus, Document, Sentence, Source},
+    xml::{attr, write_end, write_start, write_text_elem},
+    CorporeumError, Tokenizer,
+};
+
+/// Sentence metadata key holding the ID of the unit the sentence was read from.
+pub const XLIFF_UNIT_ID: &str = "unit_id";
+/// Sentence metadata key holding the ID of the XLIFF 2.0 segment the sentence was read from.
+pub const XLIFF_SEGMENT_ID: &str = "segment_id";
+/// Sentence metadata key holding the first note of a unit. Further notes are
+/// stored under the key suffixed with `_2`, `_3`, …
+pub const XLIFF_NOTE: &str = "note";
+
+/// Returns the metadata key of the note at `index`, see [`XLIFF_NOTE`].
+fn note_key(index: usize) -> String {
+    match index {
+        0 => XLIFF_NOTE.to_owned(),
+        _ => format!("{XLIFF_NOTE}_{}", index + 1),
+    }
+}
+
+/// Language used when the file does not specify one.
+const UNDEFINED_LANG: &str = "und";
+
+/// Elements whose content is not part of the translatable text.
+const SKIPPED: [&[u8]; 9] = [
+    b"alt-trans",
+    b"seg-source",
+    b"ignorable",
+    b"originalData",
+    b"ph",
+    b"bpt",
+    b"ept",
+    b"it",
+    b"sub",
+];
+
+/// Version of the XLIFF format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XliffVersion {
+    /// XLIFF 1.2 (`trans-unit` elements).
+    V1_2,
+    /// XLIFF 2.0 (`unit` and `segment` elements).
+    V2_0,
+}
+
+#[derive(Default)]
+struct Segment {
+    id: Option<String>,
+    source: String,
+    target: String,
+}
+
+#[derive(Default)]
+struct Unit {
+    id: String,
+    notes: Vec<String>,
+    segments: Vec<Segment>,
+}
+
+#[derive(Clone, Copy)]
+enum Capture {
+    Source,
+    Target,
+    Note,
+}
+
+impl Unit {
+    /// Converts the unit into sentences and appends them to the document.
+    fn flush(
+        self,
+        doc: &mut Document,
+        src: &Tokenizer,
+        trg: &Tokenizer,
+    ) -> Result<(), CorporeumError> {
+        let mut ids = free_ids(doc.sentences.iter().map(|s| s.id).max());
+        for segment in self.segments {
+            let text_tokens = src.tokenize(&segment.source);
+            if text_tokens.is_empty() {
+                continue;
+            }
+
+            let mut sent = Sentence::<Source>::new(next_free(&mut ids, "sentence")?, src.lang());
+            sent.add_text_tokens(&text_tokens)?;
+            sent.set_meta(XLIFF_UNIT_ID, &self.id);
+            if let Some(id) = &segment.id {
+                sent.set_meta(XLIFF_SEGMENT_ID, id);
+            }
+            for (i, note) in self.notes.iter().enumerate() {
+                sent.set_meta(&note_key(i), note);
+            }
+
+            let mut translation = sent.create_translation(trg.lang())?;
+            translation.add_text_tokens(&trg.tokenize(&segment.target))?;
+            sent.add_translation(translation);
+
+            doc.add_sentence(sent)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Corpus {
+    /// Reads an XLIFF 1.2 or 2.0 file and adds its contents to the corpus.
+    /// Returns the IDs of the new documents.
+    ///
+    /// Every `<file>` element becomes a [`Document`](crate::Document) and every
+    /// `trans-unit` (1.2) or `segment` (2.0) becomes a source sentence with its
+    /// target as a translation. Unit and segment IDs and notes are stored as
+    /// sentence metadata under [`XLIFF_UNIT_ID`], [`XLIFF_SEGMENT_ID`] and
+    /// [`XLIFF_NOTE`]. Inline markup is dropped, only the text is kept.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, XLIFF_NOTE, XLIFF_UNIT_ID};
+    ///
+    /// let xliff = r#"<xliff version="1.2">
+    ///   <file original="app.po" source-language="en" target-language="sk" datatype="plaintext">
+    ///     <body>
+    ///       <trans-unit id="greeting">
+    ///         <source>Hello, world!</source>
+    ///         <target>Ahoj, svet!</target>
+    ///         <note>Shown on start-up</note>
+    ///         <note>Keep it short,
+    /// max. 20 characters</note>
+    ///       </trans-unit>
+    ///     </body>
+    ///   </file>
+    /// </xliff>"#;
+    ///
+    /// let mut corp = Corpus::new();
+    /// let ids = corp.import_xliff(xliff.as_bytes()).unwrap();
+    ///
+    /// let sent = &corp.doc(ids[0]).unwrap().sentences()[0];
+    /// assert_eq!(sent.meta(XLIFF_UNIT_ID), Some("greeting"));
+    /// assert_eq!(sent.translations()[0].to_text(), "Ahoj, svet!");
+    /// assert_eq!(sent.meta(XLIFF_NOTE), Some("Shown on start-up"));
+    /// assert_eq!(sent.meta("note_2"), Some("Keep it short,\nmax. 20 characters"));
+    /// ```
+    ///
+    /// # Errors
+    /// This will return an error if:
+    /// - The XML is malformed
+    /// - Reading from the source fails
+    pub fn import_xliff<R: BufRead>(&mut self, src: R) -> Result<Vec<u32>, CorporeumError> {
+        let mut reader = Reader::from_reader(src);
+        let mut buf = Vec::new();
+
+        let mut ids = Vec::new();
+        let mut src_lang = UNDEFINED_LANG.to_owned();
+        let mut trg_lang = UNDEFINED_LANG.to_owned();
+        let mut tokenizers = (Tokenizer::new(&src_lang), Tokenizer::new(&trg_lang));
+
+        let mut doc: Option<Document> = None;
+        let mut unit: Option<Unit> = None;
+        let mut capture: Option<Capture> = None;
+        let mut skip_depth = 0usize;
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(e) if skip_depth > 0 || SKIPPED.contains(&e.local_name().as_ref()) => {
+                    skip_depth += 1;
+                }
+                Event::End(_) if skip_depth > 0 => skip_depth -= 1,
+                Event::Start(e) => match e.local_name().as_ref() {
+                    b"xliff" => {
+                        if let Some(lang) = attr(&e, "srcLang")? {
+                            src_lang = lang;
+                        }
+                        if let Some(lang) = attr(&e, "trgLang")? {
+                            trg_lang = lang;
+                        }
+                    }
+                    b"file" => {
+                        if let Some(lang) = attr(&e, "source-language")? {
+                            src_lang = lang;
+                        }
+                        if let Some(lang) = attr(&e, "target-language")? {
+                            trg_lang = lang;
+                        }
+                        tokenizers = (Tokenizer::new(&src_lang), Tokenizer::new(&trg_lang));
+
+                        let mut new_doc = self.create_doc()?;
+                        if let Some(original) = attr(&e, "original")? {
+                            new_doc.set_source(&original);
+                        }
+                        if let Some(id) = attr(&e, "id")? {
+                            new_doc.set_meta("file_id", &id);
+                        }
+                        if let Some(datatype) = attr(&e, "datatype")? {
+                            new_doc.set_meta("datatype", &datatype);
+                        }
+                        doc = Some(new_doc);
+                    }
+                    b"trans-unit" | b"unit" => {
+                        unit = Some(Unit {
+                            id: attr(&e, "id")?.unwrap_or_default(),
+                            ..Unit::default()
+                        });
+                    }
+                    b"segment" => {
+                        if let Some(unit) = unit.as_mut() {
+                            unit.segments.push(Segment {
+                                id: attr(&e, "id")?,
+                                ..Segment::default()
+                            });
+                        }
+                    }
+                    b"source" | b"target" => {
+                        if let Some(unit) = unit.as_mut() {
+                            if unit.segments.is_empty() {
+                                unit.segments.push(Segment::default());
+                            }
+                            capture = Some(if e.local_name().as_ref() == b"source" {
+                                Capture::Source
+                            } else {
+                                Capture::Target
+                            });
+                        }
+                    }
+                    b"note" if unit.is_some() => {
+                        if let Some(unit) = unit.as_mut() {
+                            unit.notes.push(String::new());
+                        }
+                        capture = Some(Capture::Note);
+                    }
+                    _ => {}
+                },
+                Event::Text(e) if skip_depth == 0 => {
+                    if let (Some(capture), Some(unit)) = (capture, unit.as_mut()) {
+                        push_text(unit, capture, &e.unescape()?);
+                    }
+                }
+                Event::CData(e) if skip_depth == 0 => {
+                    if let (Some(capture), Some(unit)) = (capture, unit.as_mut()) {
+                        push_text(unit, capture, &String::from_utf8_lossy(&e));
+                    }
+                }
+                Event::End(e) => match e.local_name().as_ref() {
+                    b"source" | b"target" | b"note" => capture = None,
+                    b"trans-unit" | b"unit" => {
+                        if let (Some(unit), Some(doc)) = (unit.take(), doc.as_mut()) {
+                            unit.flush(doc, &tokenizers.0, &tokenizers.1)?;
+                        }
+                    }
+                    b"file" => {
+                        if let Some(doc) = doc.take() {
+                            if !doc.sentences().is_empty() {
+                                ids.push(doc.doc_id());
+                                self.add_doc(doc)?;
+                            }
+                        }
+                    }
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(ids)
+    }
+
+    /// Writes the corpus as an XLIFF file.
+    ///
+    /// Every document becomes a `<file>` element. Consecutive sentences sharing
+    /// the same [`XLIFF_UNIT_ID`] are written as one unit (as segments in
+    /// XLIFF 2.0, joined into one `trans-unit` in XLIFF 1.2), sentences without
+    /// it use their sentence ID. The target is the first translation in the
+    /// target language, which is taken from the first translation in the corpus.
+    ///
+    /// # Example
+    /// ```
+    /// use uniform::{Corpus, XliffVersion};
+    ///
+    /// let xliff = r#"<xliff version="2.0" srcLang="en" trgLang="de">
+    ///   <file id="f1">
+    ///     <unit id="u1">
+    ///       <segment><source>Good morning.</source><target>Guten Morgen.</target></segment>
+    ///     </unit>
+    ///   </file>
+    /// </xliff>"#;
+    ///
+    /// let mut corp = Corpus::new();
+    /// corp.import_xliff(xliff.as_bytes()).unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// corp.export_xliff(&mut out, XliffVersion::V1_2).unwrap();
+    /// let out = String::from_utf8(out).unwrap();
+    ///
+    /// assert!(out.contains(r#"<trans-unit id="u1">"#));
+    /// assert!(out.contains("<target>Guten Morgen.</target>"));
+    /// ```
+    ///
+    /// # Errors
+    /// This will return an error if writing into the destination fails.
+    pub fn export_xliff<W: Write>(
+        &self,
+        dest: W,
+        version: XliffVersion,
+    ) -> Result<(), CorporeumError> {
+        let sentences = self.documents.iter().flat_map(|doc| doc.sentences.iter());
+        let src_lang = sentences
+            .clone()
+            .next()
+            .map_or(UNDEFINED_LANG, |s| s.lang.as_str());
+        let trg_lang = sentences
+            .flat_map(|s| s.translations.iter())
+            .next()
+            .map(|t| t.lang.as_str());
+
+        let mut writer = Writer::new_with_indent(dest, b' ', 2);
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+
+        match version {
+            XliffVersion::V1_2 => write_start(
+                &mut writer,
+                "xliff",
+                &[
+                    ("version", "1.2"),
+                    ("xmlns", "urn:oasis:names:tc:xliff:document:1.2"),
+                ],
+            )?,
+            XliffVersion::V2_0 => {
+                let mut attrs = vec![
+                    ("version", "2.0"),
+                    ("xmlns", "urn:oasis:names:tc:xliff:document:2.0"),
+                    ("srcLang", src_lang),
+                ];
+                if let Some(trg_lang) = trg_lang {
+                    attrs.push(("trgLang", trg_lang));
+                }
+                write_start(&mut writer, "xliff", &attrs)?;
+            }
+        }
+
+        for doc in &self.documents {
+            let fallback_id = format!("doc{}", doc.id);
+            let file_id = doc.meta("file_id").unwrap_or(&fallback_id);
+            let original = doc.source().unwrap_or(file_id);
+
+            match version {
+                XliffVersion::V1_2 => {
+                    let mut attrs = vec![
+                        ("original", original),
+                        ("source-language", src_lang),
+                        ("datatype", doc.meta("datatype").unwrap_or("plaintext")),
+                    ];
+                    if let Some(trg_lang) = trg_lang {
+                        attrs.push(("target-language", trg_lang));
+                    }
+                    write_start(&mut writer, "file", &attrs)?;
+                    write_start(&mut writer, "body", &[])?;
+                }
+                XliffVersion::V2_0 => {
+                    let mut attrs = vec![("id", file_id)];
+                    if let Some(source) = doc.source() {
+                        attrs.push(("original", source));
+                    }
+                    write_start(&mut writer, "file", &attrs)?;
+                }
+            }
+
+            for unit in group_units(&doc.sentences) {
+                write_unit(&mut writer, &unit, version, trg_lang)?;
+            }
+
+            if version == XliffVersion::V1_2 {
+                write_end(&mut writer, "body")?;
+            }
+            write_end(&mut writer, "file")?;
+        }
+
+        write_end(&mut writer, "xliff")?;
+        Ok(())
+    }
+}
+
+fn push_text(unit: &mut Unit, capture: Capture, text: &str) {
+    let target = match capture {
+        Capture::Note => unit.notes.last_mut(),
+        Capture::Source => unit.segments.last_mut().map(|s| &mut s.source),
+        Capture::Target => unit.segments.last_mut().map(|s| &mut s.target),
+    };
+
+    if let Some(target) = target {
+        target.push_str(text);
+    }
+}
+
+/// Groups consecutive sentences sharing the same unit ID.
+fn group_units(sentences: &[Sentence<Source>]) -> Vec<Vec<&Sentence<Source>>> {
+    let unit_id = |s: &Sentence<Source>| s.meta(XLIFF_UNIT_ID).map(str::to_owned);
+
+    let mut units: Vec<Vec<&Sentence<Source>>> = Vec::new();
+    for sent in sentences {
+        match units.last_mut() {
+            Some(unit) if unit_id(sent).is_some() && unit_id(unit[0]) == unit_id(sent) => {
+                unit.push(sent);
+            }
+            _ => units.push(vec![sent]),
+        }
+    }
+    units
+}
+
+fn write_unit<W: Write>(
+    writer: &mut Writer<W>,
+    unit: &[&Sentence<Source>],
+    version: XliffVersion,
+    trg_lang: Option<&str>,
+) -> Result<(), CorporeumError> {
+    let first = unit[0];
+    let fallback_id = first.id.to_string();
+    let id = first.meta(XLIFF_UNIT_ID).unwrap_or(&fallback_id);
+
+    let target = |sent: &Sentence<Source>| {
+        sent.translations
+            .iter()
+            .find(|t| Some(t.lang.as_str()) == trg_lang)
+            .or_else(|| sent.translations.first())
+            .map(Sentence::detokenize)
+    };
+    let notes: Vec<&str> = (0..).map_while(|i| first.meta(&note_key(i))).collect();
+
+    match version {
+        XliffVersion::V1_2 => {
+            write_start(writer, "trans-unit", &[("id", id)])?;
+
+            let source: Vec<String> = unit.iter().map(|s| s.detokenize()).collect();
+            write_text_elem(writer, "source", &[], &source.join(" "))?;
+
+            let targets: Vec<String> = unit.iter().filter_map(|s| target(s)).collect();
+            if !targets.is_empty() {
+                write_text_elem(writer, "target", &[], &targets.join(" "))?;
+            }
+
+            for note in notes {
+                write_text_elem(writer, "note", &[], note)?;
+            }
+            write_end(writer, "trans-unit")?;
+        }
+        XliffVersion::V2_0 => {
+            write_start(writer, "unit", &[("id", id)])?;
+
+            if !notes.is_empty() {
+                write_start(writer, "notes", &[])?;
+                for note in notes {
+                    write_text_elem(writer, "note", &[], note)?;
+                }
+                write_end(writer, "notes")?;
+            }
+
+            for sent in unit {
+                match sent.meta(XLIFF_SEGMENT_ID) {
+                    Some(segment_id) => write_start(writer, "segment", &[("id", segment_id)])?,
+                    None => write_start(writer, "segment", &[])?,
+                }
+                write_text_elem(writer, "source", &[], &sent.detokenize())?;
+                if let Some(target) = target(sent) {
+                    write_text_elem(writer, "target", &[], &target)?;
+                }
+                write_end(writer, "segment")?;
+            }
+            write_end(writer, "unit")?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+use std::io::Write;
+
+use crate::CorporeumError;
+
+/// Returns the unescaped value of the attribute with the specified local name.
+pub(crate) fn attr(elem: &BytesStart, name: &str) -> Result<Option<String>, CorporeumError> {
+    for attr in elem.attributes() {
+        let attr = attr.map_err(quick_xml::Error::from)?;
+        if attr.key.local_name().as_ref() == name.as_bytes() {
+            return Ok(Some(attr.unescape_value()?.into_owned()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Writes `<name attrs...>text</name>`.
+pub(crate) fn write_text_elem<W: Write>(
+    writer: &mut Writer<W>,
+    name: &str,
+    attrs: &[(&str, &str)],
+    text: &str,
+) -> Result<(), CorporeumError> {
+    writer.write_event(Event::Start(
+        BytesStart::new(name).with_attributes(attrs.iter().copied()),
+    ))?;
+    writer.write_event(Event::Text(BytesText::new(text)))?;
+    writer.write_event(Event::End(BytesEnd::new(name)))?;
+    Ok(())
+}
+
+/// Writes `<name attrs...>`.
+pub(crate) fn write_start<W: Write>(
+    writer: &mut Writer<W>,
+    name: &str,
+    attrs: &[(&str, &str)],
+) -> Result<(), CorporeumError> {
+    writer.write_event(Event::Start(
+        BytesStart::new(name).with_attributes(attrs.iter().copied()),
+    ))?;
+    Ok(())
+}
+
+/// Writes `</name>`.
+pub(crate) fn write_end<W: Write>(
+    writer: &mut Writer<W>,
+    name: &str,
+) -> Result<(), CorporeumError> {
+    writer.write_event(Event::End(BytesEnd::new(name)))?;
+    Ok(())
+}